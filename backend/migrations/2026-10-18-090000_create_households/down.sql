@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE household_members;
+DROP TABLE households;
+DROP TYPE household_role;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE chore_occurrences;
+DROP TABLE chores;
+DROP TYPE chore_occurrence_status;
+DROP TYPE chore_recurrence;
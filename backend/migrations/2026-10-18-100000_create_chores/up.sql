@@ -0,0 +1,39 @@
+-- Your SQL goes here
+CREATE TYPE chore_recurrence AS ENUM ('daily', 'weekly', 'every_n_days', 'weekdays');
+CREATE TYPE chore_occurrence_status AS ENUM ('pending', 'completed', 'skipped');
+
+CREATE TABLE chores (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    household_id UUID NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    recurrence chore_recurrence NOT NULL,
+    -- Number of days between occurrences, only used by 'every_n_days'.
+    recurrence_interval INTEGER CHECK (recurrence_interval > 0),
+    -- Bitmask of weekdays (bit 0 = Monday), only used by 'weekdays'.
+    recurrence_weekdays INTEGER CHECK (recurrence_weekdays > 0 AND recurrence_weekdays < 128),
+    rotate BOOLEAN NOT NULL DEFAULT TRUE,
+    start_date DATE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP
+);
+
+SELECT diesel_manage_updated_at('chores');
+
+CREATE INDEX chores_household_id_idx ON chores(household_id);
+
+CREATE TABLE chore_occurrences (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    chore_id UUID NOT NULL REFERENCES chores(id) ON DELETE CASCADE,
+    due_date DATE NOT NULL,
+    assignee_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    status chore_occurrence_status NOT NULL DEFAULT 'pending',
+    resolved_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP,
+    UNIQUE (chore_id, due_date)
+);
+
+SELECT diesel_manage_updated_at('chore_occurrences');
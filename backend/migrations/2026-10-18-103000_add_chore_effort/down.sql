@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX chore_occurrences_resolved_by_idx;
+ALTER TABLE chores DROP COLUMN effort;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE chores ADD COLUMN effort INTEGER NOT NULL DEFAULT 1 CHECK (effort BETWEEN 1 AND 10);
+
+CREATE INDEX chore_occurrences_resolved_by_idx ON chore_occurrences(resolved_by, resolved_at)
+    WHERE status = 'completed';
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE bill_splits;
+DROP TABLE bills;
+DROP TYPE bill_split_method;
@@ -0,0 +1,33 @@
+-- Your SQL goes here
+CREATE TYPE bill_split_method AS ENUM ('equal', 'exact', 'percentage', 'shares');
+
+CREATE TABLE bills (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    household_id UUID NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    payer_id UUID NOT NULL REFERENCES users(id),
+    description TEXT NOT NULL,
+    -- Stored in the minor unit of the currency, e.g. cents.
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL CHECK (currency ~ '^[A-Z]{3}$'),
+    due_date DATE,
+    split_method bill_split_method NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP
+);
+
+SELECT diesel_manage_updated_at('bills');
+
+CREATE INDEX bills_household_id_idx ON bills(household_id);
+
+CREATE TABLE bill_splits (
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id),
+    -- The amount this member owes, in the minor unit of the bill's currency.
+    amount BIGINT NOT NULL CHECK (amount >= 0),
+    -- What was asked for: minor units, basis points or shares depending on the split method.
+    value BIGINT NOT NULL CHECK (value >= 0),
+    PRIMARY KEY (bill_id, user_id)
+);
+
+CREATE INDEX bill_splits_user_id_idx ON bill_splits(user_id);
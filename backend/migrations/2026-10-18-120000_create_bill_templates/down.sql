@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE bills DROP COLUMN template_id;
+DROP TABLE bill_template_splits;
+DROP TABLE bill_templates;
+DROP TYPE bill_recurrence;
@@ -0,0 +1,41 @@
+-- Your SQL goes here
+CREATE TYPE bill_recurrence AS ENUM ('weekly', 'monthly');
+
+CREATE TABLE bill_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    household_id UUID NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    payer_id UUID NOT NULL REFERENCES users(id),
+    description TEXT NOT NULL,
+    -- Stored in the minor unit of the currency, e.g. cents.
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    currency TEXT NOT NULL CHECK (currency ~ '^[A-Z]{3}$'),
+    split_method bill_split_method NOT NULL,
+    recurrence bill_recurrence NOT NULL,
+    -- Number of weeks or months between bills.
+    recurrence_interval INTEGER NOT NULL DEFAULT 1 CHECK (recurrence_interval > 0),
+    start_date DATE NOT NULL,
+    -- The due date of the next bill to generate.
+    next_due_date DATE NOT NULL,
+    paused_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP
+);
+
+SELECT diesel_manage_updated_at('bill_templates');
+
+CREATE INDEX bill_templates_household_id_idx ON bill_templates(household_id);
+CREATE INDEX bill_templates_next_due_date_idx ON bill_templates(next_due_date) WHERE paused_at IS NULL;
+
+CREATE TABLE bill_template_splits (
+    template_id UUID NOT NULL REFERENCES bill_templates(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id),
+    amount BIGINT NOT NULL CHECK (amount >= 0),
+    value BIGINT NOT NULL CHECK (value >= 0),
+    PRIMARY KEY (template_id, user_id)
+);
+
+-- A template only ever generates one bill per due date, however many times the scheduler runs.
+ALTER TABLE bills
+    ADD COLUMN template_id UUID REFERENCES bill_templates(id) ON DELETE SET NULL,
+    ADD CONSTRAINT bills_template_id_due_date_key UNIQUE (template_id, due_date);
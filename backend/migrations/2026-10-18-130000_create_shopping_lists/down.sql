@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE bill_items;
+DELETE FROM bills WHERE status = 'draft';
+ALTER TABLE bills
+    DROP CONSTRAINT bills_amount_check,
+    DROP COLUMN status,
+    ADD CONSTRAINT bills_amount_check CHECK (amount > 0);
+DROP TYPE bill_status;
+DROP TABLE shopping_items;
+DROP TABLE shopping_lists;
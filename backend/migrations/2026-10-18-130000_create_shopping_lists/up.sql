@@ -0,0 +1,52 @@
+-- Your SQL goes here
+CREATE TABLE shopping_lists (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    household_id UUID NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP
+);
+
+SELECT diesel_manage_updated_at('shopping_lists');
+
+CREATE INDEX shopping_lists_household_id_idx ON shopping_lists(household_id);
+
+CREATE TABLE shopping_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    list_id UUID NOT NULL REFERENCES shopping_lists(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    quantity DOUBLE PRECISION CHECK (quantity > 0),
+    unit TEXT,
+    category TEXT,
+    added_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    checked_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    checked_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP
+);
+
+SELECT diesel_manage_updated_at('shopping_items');
+
+CREATE INDEX shopping_items_list_id_idx ON shopping_items(list_id);
+
+-- Draft bills are created from a shopping trip before anyone has said how much it cost or how to split it.
+CREATE TYPE bill_status AS ENUM ('draft', 'posted');
+
+ALTER TABLE bills
+    ADD COLUMN status bill_status NOT NULL DEFAULT 'posted',
+    DROP CONSTRAINT bills_amount_check,
+    ADD CONSTRAINT bills_amount_check CHECK (amount > 0 OR (status = 'draft' AND amount = 0));
+
+CREATE TABLE bill_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bill_id UUID NOT NULL REFERENCES bills(id) ON DELETE CASCADE,
+    shopping_item_id UUID REFERENCES shopping_items(id) ON DELETE SET NULL,
+    name TEXT NOT NULL,
+    quantity DOUBLE PRECISION,
+    unit TEXT,
+    category TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX bill_items_bill_id_idx ON bill_items(bill_id);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE calendar_feed_tokens;
+DROP TABLE event_rsvps;
+DROP TABLE events;
+DROP TYPE rsvp_status;
+DROP TYPE event_frequency;
@@ -0,0 +1,42 @@
+-- Your SQL goes here
+CREATE TYPE event_frequency AS ENUM ('daily', 'weekly', 'monthly', 'yearly');
+CREATE TYPE rsvp_status AS ENUM ('going', 'maybe', 'declined');
+
+CREATE TABLE events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    household_id UUID NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    location TEXT,
+    -- Wall clock times in `timezone`, so recurring events keep their local time across daylight saving changes.
+    starts_at TIMESTAMP NOT NULL,
+    ends_at TIMESTAMP NOT NULL,
+    timezone TEXT NOT NULL,
+    all_day BOOLEAN NOT NULL DEFAULT FALSE,
+    recurrence_frequency event_frequency,
+    recurrence_interval INTEGER NOT NULL DEFAULT 1 CHECK (recurrence_interval > 0),
+    recurrence_until DATE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP,
+    CHECK (ends_at >= starts_at)
+);
+
+SELECT diesel_manage_updated_at('events');
+
+CREATE INDEX events_household_id_idx ON events(household_id);
+
+CREATE TABLE event_rsvps (
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status rsvp_status NOT NULL,
+    responded_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, user_id)
+);
+
+-- Calendar apps can't send a bearer token, so each user gets a secret to put in their feed urls instead.
+CREATE TABLE calendar_feed_tokens (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE chat_messages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    household_id UUID NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- History is read newest first, a page at a time.
+CREATE INDEX chat_messages_household_id_created_at_idx ON chat_messages(household_id, created_at DESC, id DESC);
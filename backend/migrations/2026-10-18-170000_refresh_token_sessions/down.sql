@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX refresh_tokens_user_id_idx;
+
+-- Only the most recent session of each user can be kept.
+DELETE FROM refresh_tokens
+WHERE id NOT IN (
+    SELECT DISTINCT ON (user_id) id FROM refresh_tokens ORDER BY user_id, last_used_at DESC
+);
+
+ALTER TABLE refresh_tokens
+    DROP COLUMN token,
+    DROP COLUMN device_name,
+    DROP COLUMN user_agent,
+    DROP COLUMN ip_address,
+    DROP COLUMN last_used_at,
+    ADD CONSTRAINT refresh_tokens_user_id_key UNIQUE (user_id);
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+ALTER TABLE refresh_tokens DROP CONSTRAINT refresh_tokens_user_id_key;
+
+-- Each refresh token is now a session on one device. The id identifies the session, while the token
+-- itself is a separate secret that changes every time it is used.
+ALTER TABLE refresh_tokens
+    ADD COLUMN token UUID NOT NULL UNIQUE DEFAULT gen_random_uuid(),
+    ADD COLUMN device_name TEXT,
+    ADD COLUMN user_agent TEXT,
+    ADD COLUMN ip_address TEXT,
+    ADD COLUMN last_used_at TIMESTAMP NOT NULL DEFAULT NOW();
+
+-- Existing refresh tokens keep working.
+UPDATE refresh_tokens SET token = id, last_used_at = created_at;
+
+CREATE INDEX refresh_tokens_user_id_idx ON refresh_tokens(user_id);
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- Refresh tokens that have been rotated out of a session. Seeing one of these again means it was stolen.
+CREATE TABLE used_refresh_tokens (
+    token UUID PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES refresh_tokens(id) ON DELETE CASCADE,
+    used_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX used_refresh_tokens_session_id_idx ON used_refresh_tokens(session_id);
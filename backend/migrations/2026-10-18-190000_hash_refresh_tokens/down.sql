@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM refresh_tokens;
+
+ALTER TABLE used_refresh_tokens DROP COLUMN token_hash;
+ALTER TABLE used_refresh_tokens ADD COLUMN token UUID PRIMARY KEY;
+
+ALTER TABLE refresh_tokens DROP COLUMN token_hash;
+ALTER TABLE refresh_tokens ADD COLUMN token UUID NOT NULL UNIQUE DEFAULT gen_random_uuid();
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+-- Refresh tokens are now random secrets that are only stored hashed. The old tokens can't be carried over,
+-- so every session is ended and everyone has to log in again.
+DELETE FROM refresh_tokens;
+
+ALTER TABLE refresh_tokens DROP COLUMN token;
+ALTER TABLE refresh_tokens ADD COLUMN token_hash BYTEA NOT NULL UNIQUE;
+
+ALTER TABLE used_refresh_tokens DROP COLUMN token;
+ALTER TABLE used_refresh_tokens ADD COLUMN token_hash BYTEA PRIMARY KEY;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE revoked_access_tokens;
+
+ALTER TABLE users DROP COLUMN tokens_valid_after;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+-- Access tokens issued to a user before this time are no longer accepted.
+ALTER TABLE users ADD COLUMN tokens_valid_after TIMESTAMP;
+
+-- Individual access tokens that were revoked before they expired, by their `jti` claim.
+CREATE TABLE revoked_access_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX revoked_access_tokens_user_id_idx ON revoked_access_tokens(user_id);
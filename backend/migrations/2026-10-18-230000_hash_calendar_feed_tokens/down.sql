@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+-- The hashed tokens can't be turned back into tokens, so every feed url stops working.
+DELETE FROM calendar_feed_tokens;
+
+ALTER TABLE calendar_feed_tokens DROP COLUMN token_hash;
+ALTER TABLE calendar_feed_tokens ADD COLUMN token TEXT NOT NULL UNIQUE;
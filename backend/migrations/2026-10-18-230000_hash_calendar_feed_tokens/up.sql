@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Feed tokens are now only stored hashed. Existing tokens are hashed in place, so subscribed calendars keep working.
+ALTER TABLE calendar_feed_tokens ADD COLUMN token_hash BYTEA;
+UPDATE calendar_feed_tokens SET token_hash = sha256(convert_to(token, 'UTF8'));
+ALTER TABLE calendar_feed_tokens ALTER COLUMN token_hash SET NOT NULL;
+ALTER TABLE calendar_feed_tokens ADD CONSTRAINT calendar_feed_tokens_token_hash_key UNIQUE (token_hash);
+
+ALTER TABLE calendar_feed_tokens DROP COLUMN token;
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+-- Sessions that were ended before the access tokens issued for them expired. Every access token of a session is
+-- revoked with it, however many times the session was refreshed.
+CREATE TABLE revoked_sessions (
+    session_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX revoked_sessions_user_id_idx ON revoked_sessions(user_id);
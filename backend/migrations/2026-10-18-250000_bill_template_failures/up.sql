@@ -0,0 +1,4 @@
+-- Your SQL goes here
+-- How many times in a row generating bills from a template has failed. The template is paused once it has failed too
+-- many times, so that a broken template isn't retried forever.
+ALTER TABLE bill_templates ADD COLUMN failure_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+-- The hashed tokens can't be turned back into tokens, so every invite link stops working. Invite codes still work.
+ALTER TABLE household_invites ADD COLUMN token TEXT;
+UPDATE household_invites SET token = replace(gen_random_uuid()::text, '-', '');
+ALTER TABLE household_invites ALTER COLUMN token SET NOT NULL;
+ALTER TABLE household_invites ADD CONSTRAINT household_invites_token_key UNIQUE (token);
+
+ALTER TABLE household_invites DROP COLUMN token_hash;
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+-- Invite link tokens are now only stored hashed. Existing tokens are hashed in place, so links already sent keep working.
+ALTER TABLE household_invites ADD COLUMN token_hash BYTEA;
+UPDATE household_invites SET token_hash = sha256(convert_to(token, 'UTF8'));
+ALTER TABLE household_invites ALTER COLUMN token_hash SET NOT NULL;
+ALTER TABLE household_invites ADD CONSTRAINT household_invites_token_hash_key UNIQUE (token_hash);
+
+ALTER TABLE household_invites DROP COLUMN token;
@@ -1,6 +1,32 @@
 use super::auth::controllers as auth_routes;
 use super::auth::models as auth_models;
+use super::bill_templates::controllers as bill_template_routes;
+use super::bill_templates::models as bill_template_models;
+use super::bill_templates::recurrence::BillRecurrence;
+use super::bills::controllers as bill_routes;
+use super::bills::models as bill_models;
+use super::calendar::controllers as calendar_routes;
+use super::calendar::models as calendar_models;
+use super::chat::controllers as chat_routes;
+use super::chat::models as chat_models;
+use super::chores::controllers as chore_routes;
+use super::chores::models as chore_models;
+use super::chores::recurrence;
+use super::documents::controllers as document_routes;
+use super::documents::models as document_models;
 use super::error;
+use super::households::controllers as household_routes;
+use super::households::models as household_models;
+use super::invites::controllers as invite_routes;
+use super::invites::models as invite_models;
+use super::ledger::controllers as ledger_routes;
+use super::ledger::models as ledger_models;
+use super::shopping::controllers as shopping_routes;
+use super::shopping::models as shopping_models;
+use crate::db::bill::{BillStatus, SplitMethod};
+use crate::db::chore::OccurrenceStatus;
+use crate::db::event::{EventFrequency, RsvpStatus};
+use crate::db::household::HouseholdRole;
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
@@ -16,6 +42,84 @@ use utoipa_swagger_ui::SwaggerUi;
 		auth_routes::delete_refresh_token,
 		auth_routes::refresh_token,
 		auth_routes::get_user,
+		auth_routes::list_sessions,
+		auth_routes::revoke_session,
+		auth_routes::revoke_other_sessions,
+		auth_routes::get_public_keys,
+		auth_routes::forgot_password,
+		auth_routes::reset_password,
+		auth_routes::verify_email,
+		auth_routes::resend_verification_email,
+		auth_routes::change_password,
+		auth_routes::change_email,
+		household_routes::create_household,
+		household_routes::list_households,
+		household_routes::get_household,
+		household_routes::update_household,
+		household_routes::leave_household,
+		household_routes::update_member,
+		household_routes::delete_household,
+		invite_routes::create_invite,
+		invite_routes::list_invites,
+		invite_routes::revoke_invite,
+		invite_routes::redeem_invite,
+		chore_routes::create_chore,
+		chore_routes::list_chores,
+		chore_routes::get_chore,
+		chore_routes::update_chore,
+		chore_routes::delete_chore,
+		chore_routes::list_occurrences,
+		chore_routes::complete_occurrence,
+		chore_routes::skip_occurrence,
+		chore_routes::reassign_occurrence,
+		chore_routes::get_scoreboard,
+		bill_routes::create_bill,
+		bill_routes::list_bills,
+		bill_routes::get_bill,
+		bill_routes::update_bill,
+		bill_routes::post_bill,
+		bill_routes::delete_bill,
+		bill_template_routes::create_bill_template,
+		bill_template_routes::list_bill_templates,
+		bill_template_routes::get_bill_template,
+		bill_template_routes::update_bill_template,
+		bill_template_routes::delete_bill_template,
+		bill_template_routes::pause_bill_template,
+		bill_template_routes::resume_bill_template,
+		ledger_routes::get_ledger,
+		ledger_routes::create_settlement,
+		ledger_routes::list_settlements,
+		ledger_routes::delete_settlement,
+		shopping_routes::create_shopping_list,
+		shopping_routes::list_shopping_lists,
+		shopping_routes::get_shopping_list,
+		shopping_routes::update_shopping_list,
+		shopping_routes::delete_shopping_list,
+		shopping_routes::add_shopping_item,
+		shopping_routes::update_shopping_item,
+		shopping_routes::delete_shopping_item,
+		shopping_routes::check_shopping_item,
+		shopping_routes::uncheck_shopping_item,
+		shopping_routes::check_shopping_items,
+		shopping_routes::clear_checked_shopping_items,
+		calendar_routes::create_event,
+		calendar_routes::list_events,
+		calendar_routes::get_event,
+		calendar_routes::update_event,
+		calendar_routes::delete_event,
+		calendar_routes::rsvp_to_event,
+		calendar_routes::withdraw_rsvp,
+		calendar_routes::get_calendar_feed,
+		calendar_routes::reset_calendar_feed,
+		calendar_routes::download_calendar_feed,
+		chat_routes::chat_socket,
+		chat_routes::get_chat_history,
+		document_routes::upload_document,
+		document_routes::list_documents,
+		document_routes::get_document,
+		document_routes::download_document,
+		document_routes::update_document,
+		document_routes::delete_document,
 	),
 	components(
 		schemas(
@@ -25,6 +129,79 @@ use utoipa_swagger_ui::SwaggerUi;
 			auth_models::LoginUserRequest,
 			auth_models::RefreshTokenRequest,
 			auth_models::AuthResponse,
+			auth_models::SessionResponse,
+			auth_models::PublicKeyResponse,
+			auth_models::PublicKeysResponse,
+			auth_models::ForgotPasswordRequest,
+			auth_models::ResetPasswordRequest,
+			auth_models::VerifyEmailRequest,
+			auth_models::ChangePasswordRequest,
+			auth_models::ChangeEmailRequest,
+			household_models::CreateHouseholdRequest,
+			household_models::UpdateHouseholdRequest,
+			household_models::UpdateMemberRequest,
+			household_models::HouseholdResponse,
+			household_models::HouseholdDetailResponse,
+			household_models::HouseholdMemberResponse,
+			HouseholdRole,
+			invite_models::CreateInviteRequest,
+			invite_models::RedeemInviteRequest,
+			invite_models::InviteResponse,
+			chore_models::CreateChoreRequest,
+			chore_models::UpdateChoreRequest,
+			chore_models::ReassignOccurrenceRequest,
+			chore_models::ChoreResponse,
+			chore_models::ChoreOccurrenceResponse,
+			chore_models::ScoreboardPeriod,
+			chore_models::ScoreboardResponse,
+			chore_models::ScoreboardEntry,
+			recurrence::Recurrence,
+			recurrence::Weekday,
+			OccurrenceStatus,
+			bill_models::CreateBillRequest,
+			bill_models::UpdateBillRequest,
+			bill_models::PostBillRequest,
+			bill_models::SplitRequest,
+			bill_models::SplitPortion,
+			bill_models::BillResponse,
+			bill_models::BillSplitResponse,
+			bill_models::BillItemResponse,
+			BillStatus,
+			SplitMethod,
+			bill_template_models::CreateBillTemplateRequest,
+			bill_template_models::UpdateBillTemplateRequest,
+			bill_template_models::BillTemplateResponse,
+			BillRecurrence,
+			ledger_models::LedgerResponse,
+			ledger_models::CurrencyLedgerResponse,
+			ledger_models::MemberBalanceResponse,
+			ledger_models::TransferResponse,
+			ledger_models::CreateSettlementRequest,
+			ledger_models::SettlementResponse,
+			shopping_models::CreateShoppingListRequest,
+			shopping_models::UpdateShoppingListRequest,
+			shopping_models::ShoppingListResponse,
+			shopping_models::AddShoppingItemRequest,
+			shopping_models::UpdateShoppingItemRequest,
+			shopping_models::ShoppingItemResponse,
+			shopping_models::CheckShoppingItemsRequest,
+			shopping_models::BillDraftRequest,
+			shopping_models::CheckShoppingItemsResponse,
+			calendar_models::CreateEventRequest,
+			calendar_models::UpdateEventRequest,
+			calendar_models::EventRecurrence,
+			calendar_models::EventResponse,
+			calendar_models::RsvpResponse,
+			calendar_models::RsvpRequest,
+			calendar_models::CalendarFeedResponse,
+			EventFrequency,
+			RsvpStatus,
+			chat_models::SendChatMessageRequest,
+			chat_models::ChatMessageResponse,
+			chat_models::ChatHistoryResponse,
+			document_models::UploadDocumentRequest,
+			document_models::UpdateDocumentRequest,
+			document_models::DocumentResponse,
 		)
 	)
 )]
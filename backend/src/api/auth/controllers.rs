@@ -1,11 +1,25 @@
 use super::models::RegisterNewUserRequest;
-use crate::api::auth::models::{RefreshTokenRequest, UserResponse};
+use crate::api::auth::models::{
+    ChangeEmailRequest, ChangePasswordRequest, ForgotPasswordRequest, PublicKeysResponse,
+    RefreshTokenRequest, ResetPasswordRequest, SessionResponse, UserResponse, VerifyEmailRequest,
+};
 use crate::api::auth::utils::{
-    delete_refresh_token_if_exists, find_refresh_token, find_user_by_id,
+    self, check_verification_email_throttle, create_email_verification_token,
+    create_password_reset_token, create_session, delete_session, delete_sessions,
+    email_verification_email, find_refresh_token, find_sessions_for_user, find_used_refresh_token,
+    find_user_by_id, new_session, password_reset_email, revoke_token_family, rotate_refresh_token,
+    update_password, ClientInfo,
+};
+use crate::api::error::ErrorType::{
+    EmailAlreadyVerified, PasswordIncorrect, RefreshTokenReused, SessionNotFound, Unauthorized,
+    UserAlreadyExists,
 };
-use crate::api::error::ErrorType::Unauthorized;
 use crate::api::middleware::CurrentUser;
-use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType, ToFriendlyId};
+use crate::api::utils::secret::generate_secret;
+use crate::api::utils::validation::{field_error, ValidatedJson};
+use crate::mailer::send_in_background;
 use crate::{
     api::{
         auth::models::{AuthResponse, LoginUserRequest},
@@ -13,19 +27,25 @@ use crate::{
             create_new_user, find_user_by_email, generate_auth_tokens, hash_password,
             verify_password,
         },
-        error::{
-            APIError, APIErrorBuilder,
-            ErrorType::{LoginIncorrect, Unknown},
-        },
+        error::{APIError, APIErrorBuilder, ErrorType::LoginIncorrect},
         utils::db::get_db_connection,
     },
     db::user::NewUser,
     AppState,
 };
-use axum::{extract::State, http::StatusCode, Extension, Json};
-use tracing::{error, info};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    Extension, Json,
+};
+use tracing::info;
+
+/// Services that find a key they don't know fetch the keys again, so they only need to be cached briefly.
+const PUBLIC_KEYS_CACHE_CONTROL: &str = "public, max-age=300";
 
 /// Register a new user
+///
+/// An email is sent to the user to verify their address.
 #[utoipa::path(
     post,
     path = "/auth/register",
@@ -42,7 +62,8 @@ use tracing::{error, info};
 )]
 pub async fn register(
     State(state): State<AppState>,
-    Json(payload): Json<RegisterNewUserRequest>,
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<RegisterNewUserRequest>,
 ) -> Result<(StatusCode, Json<AuthResponse>), APIError> {
     info!(email = payload.email, "registering new user");
 
@@ -60,7 +81,24 @@ pub async fn register(
     )
     .await?;
 
-    let tokens = generate_auth_tokens(&mut conn, &user, &state.settings.auth.private_key).await?;
+    let token = create_email_verification_token(&mut conn, user.id, &user.email).await?;
+    send_in_background(
+        state.mailer.clone(),
+        email_verification_email(
+            &user.first_name,
+            &user.email,
+            &token,
+            &state.settings.server,
+        ),
+    );
+
+    let refresh_token = generate_secret();
+    let session = create_session(
+        &mut conn,
+        new_session(user.id, &refresh_token, payload.device_name, client),
+    )
+    .await?;
+    let tokens = generate_auth_tokens(&user, &session, refresh_token, &state.keys)?;
 
     Ok((StatusCode::CREATED, Json(tokens)))
 }
@@ -76,11 +114,14 @@ pub async fn register(
     ),
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Invalid request", body = APIError),
+        (status = 401, description = "Email or password incorrect", body = APIError),
     )
 )]
 pub async fn login(
     State(state): State<AppState>,
-    Json(payload): Json<LoginUserRequest>,
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<LoginUserRequest>,
 ) -> Result<(StatusCode, Json<AuthResponse>), APIError> {
     info!(email = payload.email, "logging in");
 
@@ -104,19 +145,24 @@ pub async fn login(
             .build());
     }
 
-    let user = user.ok_or_else(|| {
-        error!("User not found after logging in. This should never happen.");
-        APIErrorBuilder::new(Unknown).build()
-    })?;
+    let user =
+        user.ok_or_else(|| APIErrorBuilder::from_error("user not found after logging in").build())?;
 
-    let tokens = generate_auth_tokens(&mut conn, &user, &state.settings.auth.private_key).await?;
+    let refresh_token = generate_secret();
+    let session = create_session(
+        &mut conn,
+        new_session(user.id, &refresh_token, payload.device_name, client),
+    )
+    .await?;
+    let tokens = generate_auth_tokens(&user, &session, refresh_token, &state.keys)?;
 
     Ok((StatusCode::OK, Json(tokens)))
 }
 
 /// Logout the current user
 ///
-/// This will invalidate the refresh token
+/// This will invalidate the access and refresh tokens of the current session. Sessions on other devices are
+/// not affected.
 #[utoipa::path(
     delete,
     path = "/auth/refresh_token",
@@ -133,14 +179,25 @@ pub async fn delete_refresh_token(
     Extension(user): Extension<CurrentUser>,
 ) -> Result<StatusCode, APIError> {
     let mut conn = get_db_connection(&state.database_pool).await?;
-    delete_refresh_token_if_exists(&mut conn, user.id).await?;
+    match user.session_id {
+        Some(session_id) => {
+            delete_session(&mut conn, user.id, session_id).await?;
+            state.revocations.revoke_token(&mut conn, &user).await?;
+        }
+        // tokens from before sessions were tracked can't tell which session is theirs
+        None => {
+            delete_sessions(&mut conn, user.id, None).await?;
+            state.revocations.revoke_all(&mut conn, user.id).await?;
+        }
+    }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// Use a refresh token to get a new access token
 ///
-/// The refresh token will be invalidated after this request
+/// The refresh token will be invalidated after this request. Using it again signs the session out, as it
+/// means the token has been stolen.
 #[utoipa::path(
     post,
     path = "/auth/refresh_token",
@@ -150,25 +207,54 @@ pub async fn delete_refresh_token(
         content = RefreshTokenRequest
     ),
     responses(
-
+        (status = 200, description = "Refresh successful", body = AuthResponse),
+        (status = 401, description = "Refresh token invalid, expired or already used", body = APIError),
     )
 )]
 pub async fn refresh_token(
     State(state): State<AppState>,
-    Json(payload): Json<RefreshTokenRequest>,
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<RefreshTokenRequest>,
 ) -> Result<(StatusCode, Json<AuthResponse>), APIError> {
     let mut conn = get_db_connection(&state.database_pool).await?;
 
-    let token = find_refresh_token(&mut conn, &payload.refresh_token).await?;
-    let token = token.filter(|token| !token.is_expired()).ok_or(
-        APIErrorBuilder::new(Unauthorized)
+    let Some(token) = find_refresh_token(&mut conn, &payload.refresh_token).await? else {
+        // a token that was already rotated out has been copied, so neither copy can be trusted
+        if let Some(session_id) = find_used_refresh_token(&mut conn, &payload.refresh_token).await?
+        {
+            if let Some(user_id) = revoke_token_family(&mut conn, session_id).await? {
+                state
+                    .revocations
+                    .revoke_sessions(&mut conn, user_id, &[session_id])
+                    .await?;
+            }
+            return Err(APIErrorBuilder::new(RefreshTokenReused).build());
+        }
+
+        return Err(APIErrorBuilder::new(Unauthorized)
             .detail("The token you provided is expired.")
-            .build(),
-    )?;
+            .build());
+    };
+
+    if token.is_expired() {
+        return Err(APIErrorBuilder::new(Unauthorized)
+            .detail("The token you provided is expired.")
+            .build());
+    }
 
     let user = find_user_by_id(&mut conn, &token.user_id).await?;
 
-    let tokens = generate_auth_tokens(&mut conn, &user, &state.settings.auth.private_key).await?;
+    let refresh_token = generate_secret();
+    let Some(session) = rotate_refresh_token(&mut conn, &token, &refresh_token, &client).await?
+    else {
+        revoke_token_family(&mut conn, token.id).await?;
+        state
+            .revocations
+            .revoke_sessions(&mut conn, token.user_id, &[token.id])
+            .await?;
+        return Err(APIErrorBuilder::new(RefreshTokenReused).build());
+    };
+    let tokens = generate_auth_tokens(&user, &session, refresh_token, &state.keys)?;
 
     Ok((StatusCode::OK, Json(tokens)))
 }
@@ -194,7 +280,403 @@ pub async fn get_user(
         email: current_user.email,
         first_name: current_user.first_name,
         last_name: current_user.last_name,
+        email_verified: current_user.email_verified,
     };
 
     Ok((StatusCode::OK, Json(response)))
 }
+
+/// List the sessions of the current user
+///
+/// Each device the user is signed in on has its own session.
+#[utoipa::path(
+    get,
+    path = "/auth/sessions",
+    tag = "auth",
+    security(
+        ("api_token" = [])
+    ),
+    responses(
+        (status = 200, description = "Success", body = [SessionResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+    )
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(StatusCode, Json<Vec<SessionResponse>>), APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let sessions = find_sessions_for_user(&mut conn, current_user.id).await?;
+
+    let response = sessions
+        .into_iter()
+        .map(|session| SessionResponse::new(session, current_user.session_id))
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Revoke a session
+///
+/// The device can no longer refresh its access token, so it is signed out once its current one expires.
+#[utoipa::path(
+    delete,
+    path = "/auth/sessions/{session_id}",
+    tag = "auth",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("session_id" = String, Path, description = "The session id")
+    ),
+    responses(
+        (status = 204, description = "Revoked session successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Session not found", body = APIError),
+    )
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let session_id = parse_friendly_id(&session_id, ItemIdType::Session)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+
+    info!(user_id = %current_user.id, session_id = %session_id, "revoking session");
+    if !delete_session(&mut conn, current_user.id, session_id).await? {
+        return Err(APIErrorBuilder::new(SessionNotFound).build());
+    }
+    state
+        .revocations
+        .revoke_sessions(&mut conn, current_user.id, &[session_id])
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Revoke every other session
+///
+/// Signs the current user out on every device except this one.
+#[utoipa::path(
+    delete,
+    path = "/auth/sessions/others",
+    tag = "auth",
+    security(
+        ("api_token" = [])
+    ),
+    responses(
+        (status = 204, description = "Revoked sessions successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+    )
+)]
+pub async fn revoke_other_sessions(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<StatusCode, APIError> {
+    let session_id = current_user.session_id.ok_or_else(|| {
+        APIErrorBuilder::new(Unauthorized)
+            .detail("Your token does not belong to a session. Please log in again.")
+            .build()
+    })?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+
+    info!(user_id = %current_user.id, "revoking other sessions");
+    let ended = delete_sessions(&mut conn, current_user.id, Some(session_id)).await?;
+    state
+        .revocations
+        .revoke_sessions(&mut conn, current_user.id, &ended)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the keys access tokens are signed with
+///
+/// Lets other services verify access tokens. A token names the key it was signed with by the `kid` claim in its
+/// footer; if that key isn't listed, fetch the keys again as a new one may have been added.
+#[utoipa::path(
+    get,
+    path = "/auth/keys",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Success", body = PublicKeysResponse),
+    )
+)]
+pub async fn get_public_keys(
+    State(state): State<AppState>,
+) -> (
+    StatusCode,
+    [(header::HeaderName, &'static str); 1],
+    Json<PublicKeysResponse>,
+) {
+    (
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, PUBLIC_KEYS_CACHE_CONTROL)],
+        Json(PublicKeysResponse::from(&state.keys)),
+    )
+}
+
+/// Ask for a password reset email
+///
+/// If an account exists for the email, a link to choose a new password is sent to it. The response is the same
+/// either way, so it can't be used to find out who has an account.
+#[utoipa::path(
+    post,
+    path = "/auth/password/forgot",
+    tag = "auth",
+    request_body(
+        content_type = "application/json",
+        content = ForgotPasswordRequest
+    ),
+    responses(
+        (status = 202, description = "Reset email sent if the account exists"),
+        (status = 400, description = "Invalid request", body = APIError),
+    )
+)]
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<ForgotPasswordRequest>,
+) -> Result<StatusCode, APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let Some(user) = find_user_by_email(&mut conn, &payload.email).await? else {
+        info!("password reset requested for unknown email");
+        return Ok(StatusCode::ACCEPTED);
+    };
+
+    let token = create_password_reset_token(&mut conn, user.id).await?;
+    let email = password_reset_email(&user, &token, &state.settings.server);
+
+    info!(user_id = %user.id, "sending password reset email");
+    send_in_background(state.mailer.clone(), email);
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Choose a new password with a password reset token
+///
+/// Signs the user out everywhere, so they need to log in again with the new password.
+#[utoipa::path(
+    post,
+    path = "/auth/password/reset",
+    tag = "auth",
+    request_body(
+        content_type = "application/json",
+        content = ResetPasswordRequest
+    ),
+    responses(
+        (status = 204, description = "Password reset successfully"),
+        (status = 400, description = "Invalid request or reset token", body = APIError),
+    )
+)]
+pub async fn reset_password(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<ResetPasswordRequest>,
+) -> Result<StatusCode, APIError> {
+    let hashed_password = hash_password(&payload.password)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let user_id = utils::reset_password(&mut conn, &payload.token, hashed_password).await?;
+
+    delete_sessions(&mut conn, user_id, None).await?;
+    state.revocations.revoke_all(&mut conn, user_id).await?;
+
+    info!(user_id = %user_id, "reset password");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Verify an email address
+///
+/// Uses the token from the link in a verification email. Access tokens issued before this still show the email
+/// as unverified, so refresh the access token afterwards.
+#[utoipa::path(
+    post,
+    path = "/auth/email/verify",
+    tag = "auth",
+    request_body(
+        content_type = "application/json",
+        content = VerifyEmailRequest
+    ),
+    responses(
+        (status = 204, description = "Email verified successfully"),
+        (status = 400, description = "Invalid request or verification token", body = APIError),
+    )
+)]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<VerifyEmailRequest>,
+) -> Result<StatusCode, APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let user_id = utils::verify_email(&mut conn, &payload.token).await?;
+
+    info!(user_id = %user_id, "verified email");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Send another verification email
+///
+/// Can only be asked for once a minute.
+#[utoipa::path(
+    post,
+    path = "/auth/email/verify/resend",
+    tag = "auth",
+    security(
+        ("api_token" = [])
+    ),
+    responses(
+        (status = 202, description = "Verification email sent"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 409, description = "Email already verified", body = APIError),
+        (status = 429, description = "A verification email was sent too recently", body = APIError),
+    )
+)]
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<StatusCode, APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+
+    // the token might be from before the email was verified
+    let user = find_user_by_id(&mut conn, &current_user.id).await?;
+    if user.email_verified_at.is_some() {
+        return Err(APIErrorBuilder::new(EmailAlreadyVerified).build());
+    }
+
+    check_verification_email_throttle(&mut conn, user.id).await?;
+
+    let token = create_email_verification_token(&mut conn, user.id, &user.email).await?;
+    send_in_background(
+        state.mailer.clone(),
+        email_verification_email(
+            &user.first_name,
+            &user.email,
+            &token,
+            &state.settings.server,
+        ),
+    );
+
+    info!(user_id = %user.id, "resent verification email");
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Change the password of the current user
+///
+/// Signs the user out of their other sessions. Access tokens issued before this stop working, so refresh the
+/// access token of this session afterwards.
+#[utoipa::path(
+    put,
+    path = "/auth/password",
+    tag = "auth",
+    security(
+        ("api_token" = [])
+    ),
+    request_body(
+        content_type = "application/json",
+        content = ChangePasswordRequest
+    ),
+    responses(
+        (status = 204, description = "Password changed successfully"),
+        (status = 400, description = "Invalid request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "Current password incorrect", body = APIError),
+    )
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<ChangePasswordRequest>,
+) -> Result<StatusCode, APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let user = find_user_by_id(&mut conn, &current_user.id).await?;
+    if verify_password(&payload.current_password, &user.password).is_err() {
+        info!(user_id = %user.id, "failed to change password");
+        return Err(APIErrorBuilder::new(PasswordIncorrect).build());
+    }
+
+    let hashed_password = hash_password(&payload.new_password)?;
+    update_password(&mut conn, user.id, hashed_password).await?;
+
+    delete_sessions(&mut conn, user.id, current_user.session_id).await?;
+    state.revocations.revoke_all(&mut conn, user.id).await?;
+
+    info!(user_id = %user.id, "changed password");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Change the email of the current user
+///
+/// A verification email is sent to the new address, which replaces the current one once it has been verified.
+/// Signs the user out of their other sessions. Access tokens issued before this stop working, so refresh the
+/// access token of this session afterwards.
+#[utoipa::path(
+    put,
+    path = "/auth/email",
+    tag = "auth",
+    security(
+        ("api_token" = [])
+    ),
+    request_body(
+        content_type = "application/json",
+        content = ChangeEmailRequest
+    ),
+    responses(
+        (status = 202, description = "Verification email sent to the new address"),
+        (status = 400, description = "Invalid request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "Password incorrect", body = APIError),
+        (status = 409, description = "Conflict. A user with the email already exists.", body = APIError),
+        (status = 429, description = "A verification email was sent too recently", body = APIError),
+    )
+)]
+pub async fn change_email(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<ChangeEmailRequest>,
+) -> Result<StatusCode, APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let user = find_user_by_id(&mut conn, &current_user.id).await?;
+    if verify_password(&payload.password, &user.password).is_err() {
+        info!(user_id = %user.id, "failed to change email");
+        return Err(APIErrorBuilder::new(PasswordIncorrect).build());
+    }
+
+    if payload.email == user.email {
+        return Err(field_error(
+            "email",
+            "unchanged",
+            "The new email is the same as the current one.",
+        ));
+    }
+    if find_user_by_email(&mut conn, &payload.email)
+        .await?
+        .is_some()
+    {
+        return Err(APIErrorBuilder::new(UserAlreadyExists).build());
+    }
+
+    check_verification_email_throttle(&mut conn, user.id).await?;
+
+    let token = create_email_verification_token(&mut conn, user.id, &payload.email).await?;
+    send_in_background(
+        state.mailer.clone(),
+        email_verification_email(
+            &user.first_name,
+            &payload.email,
+            &token,
+            &state.settings.server,
+        ),
+    );
+
+    delete_sessions(&mut conn, user.id, current_user.session_id).await?;
+    state.revocations.revoke_all(&mut conn, user.id).await?;
+
+    info!(user_id = %user.id, "requested email change");
+
+    Ok(StatusCode::ACCEPTED)
+}
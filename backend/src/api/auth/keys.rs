@@ -0,0 +1,98 @@
+//! The keys access tokens are signed with.
+//!
+//! Every key is identified by its PASERK id, which tokens carry as the `kid` claim in their footer. Tokens are
+//! signed with the newest key that has a private key, and are accepted if they were signed by any key that
+//! hasn't been retired. Rotating keys is done by adding a new one, then retiring the old one once the tokens
+//! it signed have expired.
+
+use crate::config::Auth;
+use anyhow::{anyhow, Context};
+use pasetors::footer::Footer;
+use pasetors::keys::{AsymmetricPublicKey, AsymmetricSecretKey};
+use pasetors::paserk::{FormatAsPaserk, Id};
+use pasetors::version4::V4;
+
+pub struct SigningKey {
+    pub id: String,
+    pub key: AsymmetricSecretKey<V4>,
+    pub footer: Footer,
+}
+
+pub struct VerificationKey {
+    /// The PASERK id of the key, as found in the `kid` claim of the tokens it signed.
+    pub id: String,
+    pub key: AsymmetricPublicKey<V4>,
+    /// The key in PASERK `k4.public` form.
+    pub paserk: String,
+    pub footer: Footer,
+}
+
+pub struct KeySet {
+    signing: SigningKey,
+    verification: Vec<VerificationKey>,
+}
+
+impl KeySet {
+    pub fn new(settings: &Auth) -> anyhow::Result<Self> {
+        let mut signing = None;
+        let mut verification = Vec::new();
+
+        for key in settings.keys.iter().filter(|key| !key.retired) {
+            let public_key = AsymmetricPublicKey::<V4>::try_from(key.public_key.as_str())
+                .map_err(|e| anyhow!(e))
+                .context("invalid public key")?;
+
+            let id = Id::from(&public_key);
+            let mut footer = Footer::new();
+            footer.key_id(&id);
+
+            let mut paserk_id = String::new();
+            id.fmt(&mut paserk_id).map_err(|e| anyhow!(e))?;
+            let mut paserk = String::new();
+            public_key.fmt(&mut paserk).map_err(|e| anyhow!(e))?;
+
+            if let Some(private_key) = &key.private_key {
+                let private_key = AsymmetricSecretKey::<V4>::try_from(private_key.as_str())
+                    .map_err(|e| anyhow!(e))
+                    .context("invalid private key")?;
+
+                // later keys in the list are newer
+                signing = Some(SigningKey {
+                    id: paserk_id.clone(),
+                    key: private_key,
+                    footer: footer.clone(),
+                });
+            }
+
+            verification.push(VerificationKey {
+                id: paserk_id,
+                key: public_key,
+                paserk,
+                footer,
+            });
+        }
+
+        let signing =
+            signing.context("no key that isn't retired has a private key to sign tokens with")?;
+
+        Ok(Self {
+            signing,
+            verification,
+        })
+    }
+
+    /// The key new tokens are signed with.
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.signing
+    }
+
+    /// The keys tokens are accepted from.
+    pub fn verification_keys(&self) -> &[VerificationKey] {
+        &self.verification
+    }
+
+    /// Finds the key a token was signed with by its PASERK id.
+    pub fn verification_key(&self, id: &str) -> Option<&VerificationKey> {
+        self.verification.iter().find(|key| key.id == id)
+    }
+}
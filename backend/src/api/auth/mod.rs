@@ -1,19 +1,35 @@
 use super::middleware::auth;
 use crate::AppState;
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, post, put};
 use axum::{middleware, Router};
-use controllers::{delete_refresh_token, get_user, login, refresh_token, register};
+use controllers::{
+    change_email, change_password, delete_refresh_token, forgot_password, get_public_keys,
+    get_user, list_sessions, login, refresh_token, register, resend_verification_email,
+    reset_password, revoke_other_sessions, revoke_session, verify_email,
+};
 
 pub mod controllers;
+pub mod keys;
 pub mod models;
-mod utils;
+pub mod revocation;
+pub(crate) mod utils;
 
 pub fn get_router(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/logout", delete(delete_refresh_token))
         .route("/user", get(get_user))
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/others", delete(revoke_other_sessions))
+        .route("/sessions/:session_id", delete(revoke_session))
+        .route("/email/verify/resend", post(resend_verification_email))
+        .route("/password", put(change_password))
+        .route("/email", put(change_email))
         .route_layer(middleware::from_fn_with_state(state.clone(), auth))
         .route("/register", post(register))
         .route("/login", post(login))
         .route("/refresh_token", post(refresh_token))
+        .route("/keys", get(get_public_keys))
+        .route("/password/forgot", post(forgot_password))
+        .route("/password/reset", post(reset_password))
+        .route("/email/verify", post(verify_email))
 }
@@ -1,8 +1,11 @@
 use super::super::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::api::auth::keys::KeySet;
+use crate::db::refresh_token::RefreshToken;
 use crate::db::user::User;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use utoipa::ToSchema;
+use uuid::Uuid;
 use validator::Validate;
 
 #[derive(Deserialize, Validate, ToSchema)]
@@ -22,6 +25,10 @@ pub struct RegisterNewUserRequest {
     #[validate(length(min = 8))]
     #[schema(example = "Password123", min_length = 8, format = "password")]
     pub password: String,
+
+    /// A name for the device being signed in on, shown in the list of sessions. Longer names are cut short.
+    #[schema(example = "John's laptop", max_length = 64)]
+    pub device_name: Option<String>,
 }
 
 impl fmt::Debug for RegisterNewUserRequest {
@@ -31,6 +38,7 @@ impl fmt::Debug for RegisterNewUserRequest {
             .field("first_name", &self.first_name)
             .field("last_name", &self.last_name)
             .field("password", &"********")
+            .field("device_name", &self.device_name)
             .finish()
     }
 }
@@ -44,6 +52,10 @@ pub struct LoginUserRequest {
     #[validate(length(min = 8))]
     #[schema(example = "Password123", min_length = 8, format = "password")]
     pub password: String,
+
+    /// A name for the device being signed in on, shown in the list of sessions. Longer names are cut short.
+    #[schema(example = "John's laptop", max_length = 64)]
+    pub device_name: Option<String>,
 }
 
 impl fmt::Debug for LoginUserRequest {
@@ -51,6 +63,7 @@ impl fmt::Debug for LoginUserRequest {
         f.debug_struct("LoginUserRequest")
             .field("email", &self.email)
             .field("password", &"********")
+            .field("device_name", &self.device_name)
             .finish()
     }
 }
@@ -69,12 +82,85 @@ impl fmt::Debug for RefreshTokenRequest {
     }
 }
 
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ForgotPasswordRequest {
+    #[validate(email)]
+    #[schema(example = "john.smith@example.com", format = "email")]
+    pub email: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ResetPasswordRequest {
+    /// The token from the password reset link.
+    pub token: String,
+
+    #[validate(length(min = 8))]
+    #[schema(example = "Password123", min_length = 8, format = "password")]
+    pub password: String,
+}
+
+impl fmt::Debug for ResetPasswordRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResetPasswordRequest")
+            .field("token", &"********")
+            .field("password", &"********")
+            .finish()
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct VerifyEmailRequest {
+    /// The token from the verification link.
+    pub token: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[schema(example = "Password123", format = "password")]
+    pub current_password: String,
+
+    #[validate(length(min = 8))]
+    #[schema(example = "Password456", min_length = 8, format = "password")]
+    pub new_password: String,
+}
+
+impl fmt::Debug for ChangePasswordRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChangePasswordRequest")
+            .field("current_password", &"********")
+            .field("new_password", &"********")
+            .finish()
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ChangeEmailRequest {
+    /// The new email address, which is used once it has been verified.
+    #[validate(email)]
+    #[schema(example = "john.smith@example.org", format = "email")]
+    pub email: String,
+
+    /// The current password of the user.
+    #[schema(example = "Password123", format = "password")]
+    pub password: String,
+}
+
+impl fmt::Debug for ChangeEmailRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChangeEmailRequest")
+            .field("email", &self.email)
+            .field("password", &"********")
+            .finish()
+    }
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: String,
     pub email: String,
     pub first_name: String,
     pub last_name: String,
+    pub email_verified: bool,
 }
 
 impl From<User> for UserResponse {
@@ -84,6 +170,7 @@ impl From<User> for UserResponse {
             email: user.email,
             first_name: user.first_name,
             last_name: user.last_name,
+            email_verified: user.email_verified_at.is_some(),
         }
     }
 }
@@ -94,3 +181,71 @@ pub struct AuthResponse {
     pub access_token: String,
     pub refresh_token: String,
 }
+
+#[derive(Serialize, ToSchema)]
+pub struct PublicKeyResponse {
+    /// The PASERK id of the key, which tokens signed with it carry as the `kid` claim in their footer.
+    #[schema(example = "k4.pid.yMgldRRLHBLkhmcp8NG8yZrtyldbYoAjQWPv_Ma1rYVw")]
+    pub kid: String,
+    #[schema(example = "k4.public.cHFyc3R1dnd4eXp7fH1-f4CBgoOEhYaHiImKi4yNjo8")]
+    pub key: String,
+    /// Whether new tokens are signed with this key.
+    pub current: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PublicKeysResponse {
+    pub keys: Vec<PublicKeyResponse>,
+}
+
+impl From<&KeySet> for PublicKeysResponse {
+    fn from(keys: &KeySet) -> Self {
+        let current = &keys.signing_key().id;
+
+        Self {
+            keys: keys
+                .verification_keys()
+                .iter()
+                .map(|key| PublicKeyResponse {
+                    kid: key.id.clone(),
+                    key: key.paserk.clone(),
+                    current: &key.id == current,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionResponse {
+    #[schema(example = "session|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "John's laptop")]
+    pub device_name: Option<String>,
+    /// The user agent that last used the session.
+    #[schema(example = "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0")]
+    pub user_agent: Option<String>,
+    /// The address the session was last used from.
+    #[schema(example = "203.0.113.7")]
+    pub ip_address: Option<String>,
+    /// Whether this is the session of the token making the request.
+    pub current: bool,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+impl SessionResponse {
+    pub fn new(session: RefreshToken, current_session_id: Option<Uuid>) -> Self {
+        Self {
+            id: session.id.to_friendly_id(ItemIdType::Session),
+            device_name: session.device_name,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            current: current_session_id == Some(session.id),
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use super::utils::TOKEN_EXPIRY_TIME;
+use crate::api::error::APIError;
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::db::database::{Connection, ConnectionPool};
+use crate::db::revoked_access_token::NewRevokedAccessToken;
+use crate::db::revoked_session::NewRevokedSession;
+use crate::db::schema::{revoked_access_tokens, revoked_sessions, users};
+use chrono::NaiveDateTime;
+use diesel::dsl::now;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+use uuid::Uuid;
+
+/// How long the revocations of a user are cached for before they are looked up again.
+///
+/// Revocations made by this server take effect straight away. This only bounds how long other servers keep
+/// accepting a revoked token.
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Access tokens that have been revoked before they expired, cached per user so that checking them doesn't
+/// need the database on every request.
+#[derive(Default)]
+pub struct TokenRevocations {
+    users: Mutex<HashMap<Uuid, UserRevocations>>,
+    /// Bumped whenever cached revocations are forgotten, so that revocations fetched before then aren't cached.
+    generation: AtomicU64,
+}
+
+#[derive(Clone)]
+struct UserRevocations {
+    valid_after: Option<NaiveDateTime>,
+    revoked: HashSet<Uuid>,
+    revoked_sessions: HashSet<Uuid>,
+    fetched_at: Instant,
+}
+
+impl UserRevocations {
+    fn is_fresh(&self) -> bool {
+        self.fetched_at.elapsed() < CACHE_TTL
+    }
+
+    fn revokes(&self, token: &CurrentUser) -> bool {
+        self.valid_after
+            .is_some_and(|valid_after| token.issued_at < valid_after)
+            || self.revoked.contains(&token.token_id)
+            || token
+                .session_id
+                .is_some_and(|session_id| self.revoked_sessions.contains(&session_id))
+    }
+}
+
+impl TokenRevocations {
+    /// Checks whether the token of a request has been revoked.
+    pub async fn is_revoked(
+        &self,
+        pool: &ConnectionPool,
+        token: &CurrentUser,
+    ) -> Result<bool, APIError> {
+        if let Some(cached) = self.cached(token.id) {
+            return Ok(cached.revokes(token));
+        }
+
+        let generation = self.generation();
+        let mut conn = get_db_connection(pool).await?;
+        let revocations = find_revocations(&mut conn, token.id).await?;
+        let revoked = revocations.revokes(token);
+
+        let mut users = self.users.lock().unwrap();
+        users.retain(|_, cached| cached.is_fresh());
+        // a revocation made while these were being fetched may not be in them
+        if self.generation() == generation {
+            users.insert(token.id, revocations);
+        }
+
+        Ok(revoked)
+    }
+
+    /// Revokes a single access token.
+    pub async fn revoke_token(
+        &self,
+        conn: &mut Connection,
+        token: &CurrentUser,
+    ) -> Result<(), APIError> {
+        // revocations are only needed until the token would have expired anyway
+        diesel::delete(
+            revoked_access_tokens::table.filter(revoked_access_tokens::expires_at.lt(now)),
+        )
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+        diesel::insert_into(revoked_access_tokens::table)
+            .values(&NewRevokedAccessToken {
+                jti: token.token_id,
+                user_id: token.id,
+                expires_at: token.expires_at,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await
+            .map_err(APIError::from)?;
+
+        info!(user_id = %token.id, token_id = %token.token_id, "revoked access token");
+        self.forget(token.id);
+
+        Ok(())
+    }
+
+    /// Revokes every access token that has been issued for the given sessions of a user.
+    pub async fn revoke_sessions(
+        &self,
+        conn: &mut Connection,
+        user_id: Uuid,
+        session_ids: &[Uuid],
+    ) -> Result<(), APIError> {
+        if session_ids.is_empty() {
+            return Ok(());
+        }
+
+        // revocations are only needed until the last token issued for the session would have expired anyway
+        diesel::delete(revoked_sessions::table.filter(revoked_sessions::expires_at.lt(now)))
+            .execute(conn)
+            .await
+            .map_err(APIError::from)?;
+
+        let expires_at = chrono::Utc::now().naive_utc()
+            + chrono::Duration::seconds(TOKEN_EXPIRY_TIME.as_secs() as i64);
+        let revocations: Vec<NewRevokedSession> = session_ids
+            .iter()
+            .map(|&session_id| NewRevokedSession {
+                session_id,
+                user_id,
+                expires_at,
+            })
+            .collect();
+        diesel::insert_into(revoked_sessions::table)
+            .values(&revocations)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await
+            .map_err(APIError::from)?;
+
+        info!(user_id = %user_id, sessions = session_ids.len(), "revoked access tokens of sessions");
+        self.forget(user_id);
+
+        Ok(())
+    }
+
+    /// Revokes every access token that has been issued to a user so far.
+    pub async fn revoke_all(&self, conn: &mut Connection, user_id: Uuid) -> Result<(), APIError> {
+        diesel::update(users::table.find(user_id))
+            .set(users::tokens_valid_after.eq(chrono::Utc::now().naive_utc()))
+            .execute(conn)
+            .await
+            .map_err(APIError::from)?;
+
+        info!(user_id = %user_id, "revoked all access tokens");
+        self.forget(user_id);
+
+        Ok(())
+    }
+
+    fn cached(&self, user_id: Uuid) -> Option<UserRevocations> {
+        let users = self.users.lock().unwrap();
+        users
+            .get(&user_id)
+            .filter(|cached| cached.is_fresh())
+            .cloned()
+    }
+
+    fn generation(&self) -> u64 {
+        // called this way since diesel's `RunQueryDsl::load` is in scope as well
+        AtomicU64::load(&self.generation, Ordering::SeqCst)
+    }
+
+    fn forget(&self, user_id: Uuid) {
+        let mut users = self.users.lock().unwrap();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        users.remove(&user_id);
+    }
+}
+
+async fn find_revocations(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<UserRevocations, APIError> {
+    let valid_after = users::table
+        .find(user_id)
+        .select(users::tokens_valid_after)
+        .first::<Option<NaiveDateTime>>(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?;
+
+    let revoked = revoked_access_tokens::table
+        .filter(revoked_access_tokens::user_id.eq(user_id))
+        .filter(revoked_access_tokens::expires_at.gt(now))
+        .select(revoked_access_tokens::jti)
+        .load::<Uuid>(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    let revoked_sessions = revoked_sessions::table
+        .filter(revoked_sessions::user_id.eq(user_id))
+        .filter(revoked_sessions::expires_at.gt(now))
+        .select(revoked_sessions::session_id)
+        .load::<Uuid>(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(UserRevocations {
+        // a user that has been deleted can't use their tokens anymore
+        valid_after: valid_after.unwrap_or(Some(NaiveDateTime::MAX)),
+        revoked: revoked.into_iter().collect(),
+        revoked_sessions: revoked_sessions.into_iter().collect(),
+        fetched_at: Instant::now(),
+    })
+}
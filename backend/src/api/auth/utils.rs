@@ -1,27 +1,114 @@
+use crate::api::auth::keys::KeySet;
 use crate::api::auth::models::AuthResponse;
-use crate::api::error::ErrorType::{Unauthorized, UserAlreadyExists};
+use crate::api::error::ErrorType::{
+    EmailNotVerified, InvalidEmailVerificationToken, InvalidPasswordResetToken,
+    VerificationEmailThrottled,
+};
 use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::secret::{generate_secret, hash_secret};
+use crate::config::{App, Verification, VerifiedAction};
 use crate::db::database::Connection;
-use crate::db::refresh_token::{NewRefreshToken, RefreshToken};
-use crate::db::schema::{refresh_tokens, users};
+use crate::db::email_verification_token::NewEmailVerificationToken;
+use crate::db::password_reset_token::NewPasswordResetToken;
+use crate::db::refresh_token::{NewRefreshToken, NewUsedRefreshToken, RefreshToken};
+use crate::db::schema::{
+    email_verification_tokens, password_reset_tokens, refresh_tokens, used_refresh_tokens, users,
+};
 use crate::db::user::{NewUser, User};
+use crate::mailer::Email;
 use argon2::password_hash::rand_core::OsRng;
 use argon2::password_hash::SaltString;
 use argon2::{Argon2, PasswordHasher, PasswordVerifier};
-use diesel::dsl::exists;
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::header;
+use axum::http::request::Parts;
+use diesel::dsl::now;
+use diesel::pg::expression::extensions::IntervalDsl;
 use diesel::prelude::*;
-use diesel::{select, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use diesel::SelectableHelper;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
 use pasetors::claims::Claims;
 use pasetors::errors::Error as ClaimError;
-use pasetors::keys::AsymmetricSecretKey;
 use pasetors::public;
-use pasetors::version4::V4;
+use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::time::Duration;
-use tracing::{error, warn};
+use tracing::warn;
 use uuid::Uuid;
 
-const TOKEN_EXPIRY_TIME: Duration = Duration::new(30 * 60, 0); // 30 minutes
+pub const TOKEN_EXPIRY_TIME: Duration = Duration::new(30 * 60, 0); // 30 minutes
+
+/// How long a session lasts without being used.
+const SESSION_LIFETIME_DAYS: i64 = 7;
+
+/// How long a password reset link can be used for.
+const PASSWORD_RESET_LIFETIME_HOURS: i64 = 1;
+
+/// How long an email verification link can be used for.
+const EMAIL_VERIFICATION_LIFETIME_HOURS: i64 = 24;
+
+/// How long a user has to wait before they can be sent another verification email.
+const VERIFICATION_EMAIL_INTERVAL_SECONDS: i64 = 60;
+
+const MAX_USER_AGENT_LENGTH: usize = 512;
+const MAX_DEVICE_NAME_LENGTH: usize = 64;
+
+/// Details of the client making a request, recorded against its session.
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    /// The address the request came from. Behind a reverse proxy this is the address of the proxy.
+    pub ip_address: Option<String>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+        Ok(Self {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+
+/// Describes a new session for a user on the device making the request, which is refreshed with `refresh_token`.
+pub fn new_session(
+    user_id: Uuid,
+    refresh_token: &str,
+    device_name: Option<String>,
+    client: ClientInfo,
+) -> NewRefreshToken {
+    let device_name = device_name
+        .map(|name| {
+            name.trim()
+                .chars()
+                .take(MAX_DEVICE_NAME_LENGTH)
+                .collect::<String>()
+        })
+        .filter(|name| !name.is_empty());
+
+    NewRefreshToken {
+        user_id,
+        device_name,
+        user_agent: client.user_agent,
+        ip_address: client.ip_address,
+        token_hash: hash_secret(refresh_token),
+    }
+}
 
 pub fn verify_password(password: &str, hash: &str) -> Result<(), argon2::password_hash::Error> {
     let argon2 = Argon2::default();
@@ -36,10 +123,9 @@ pub fn hash_password(password: &str) -> Result<String, APIError> {
     let salt = SaltString::generate(&mut OsRng);
     let argon2 = Argon2::default();
 
-    let password_hash = argon2.hash_password(password_bytes, &salt).map_err(|err| {
-        error!(error = %err, "failed to hash password");
-        APIErrorBuilder::from_error(err).build()
-    })?;
+    let password_hash = argon2
+        .hash_password(password_bytes, &salt)
+        .map_err(|err| APIErrorBuilder::from_error(err).build())?;
 
     Ok(password_hash.to_string())
 }
@@ -50,29 +136,18 @@ pub async fn create_new_user(conn: &mut Connection, user: NewUser) -> Result<Use
         .returning(User::as_returning())
         .get_result(conn)
         .await
-        .map_err(|e| {
-            warn!(email = user.email, "failed to register new user: {}", e);
-            APIErrorBuilder::new(UserAlreadyExists)
-                .cause(e)
-                .detail("If you already have an account, try logging in.")
-                .with_field("email", user.email.into())
-                .build()
-        })
+        .map_err(APIError::from)
 }
 
-pub async fn generate_auth_tokens(
-    conn: &mut Connection,
+/// Issues an access token for a session, along with the session's refresh token.
+pub fn generate_auth_tokens(
     user: &User,
-    private_key: &str,
+    session: &RefreshToken,
+    refresh_token: String,
+    keys: &KeySet,
 ) -> Result<AuthResponse, APIError> {
-    let refresh_token = generate_new_refresh_token(conn, user.id)
-        .await?
-        .id
-        .to_string();
-    let access_token = generate_auth_token(user, private_key).map_err(|e| {
-        error!(error = %e, "failed to generate auth token");
-        APIErrorBuilder::from_error(e).build()
-    })?;
+    let access_token = generate_auth_token(user, session.id, keys)
+        .map_err(|e| APIErrorBuilder::from_error(e).build())?;
 
     Ok(AuthResponse {
         access_token,
@@ -80,49 +155,407 @@ pub async fn generate_auth_tokens(
     })
 }
 
-pub async fn generate_new_refresh_token(
+/// Starts a new session for a user, clearing out any of their sessions that have expired.
+pub async fn create_session(
     conn: &mut Connection,
-    user_id: Uuid,
+    session: NewRefreshToken,
 ) -> Result<RefreshToken, APIError> {
-    delete_refresh_token_if_exists(conn, user_id).await?;
+    diesel::delete(
+        refresh_tokens::table
+            .filter(refresh_tokens::user_id.eq(session.user_id))
+            .filter(refresh_tokens::expires_at.lt(now)),
+    )
+    .execute(conn)
+    .await
+    .map_err(APIError::from)?;
 
     diesel::insert_into(refresh_tokens::table)
-        .values(&NewRefreshToken { user_id })
-        .get_result::<RefreshToken>(conn)
+        .values(&session)
+        .returning(RefreshToken::as_returning())
+        .get_result(conn)
         .await
-        .map_err(|e| {
-            error!(error = %e, "failed to insert refresh token");
-            APIErrorBuilder::from_error(e).build()
-        })
+        .map_err(APIError::from)
+}
+
+/// Replaces the refresh token of a session with `refresh_token` and extends it, recording the client that used it.
+///
+/// The old token is kept so that it is recognised if it is presented again. Returns `None` if a concurrent
+/// request rotated the token first.
+pub async fn rotate_refresh_token(
+    conn: &mut Connection,
+    session: &RefreshToken,
+    refresh_token: &str,
+    client: &ClientInfo,
+) -> Result<Option<RefreshToken>, APIError> {
+    let session_id = session.id;
+    let old_token_hash = session.token_hash.clone();
+    let new_token_hash = hash_secret(refresh_token);
+    let user_agent = client.user_agent.clone();
+    let ip_address = client.ip_address.clone();
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            // Matching on the old token means only one of several concurrent refreshes can win.
+            let rotated = diesel::update(
+                refresh_tokens::table
+                    .filter(refresh_tokens::id.eq(session_id))
+                    .filter(refresh_tokens::token_hash.eq(&old_token_hash)),
+            )
+            .set((
+                refresh_tokens::token_hash.eq(new_token_hash),
+                refresh_tokens::expires_at.eq(now + SESSION_LIFETIME_DAYS.days()),
+                refresh_tokens::last_used_at.eq(now),
+                refresh_tokens::user_agent.eq(user_agent),
+                refresh_tokens::ip_address.eq(ip_address),
+            ))
+            .returning(RefreshToken::as_returning())
+            .get_result(conn)
+            .await
+            .optional()?;
+
+            if rotated.is_some() {
+                diesel::insert_into(used_refresh_tokens::table)
+                    .values(&NewUsedRefreshToken {
+                        token_hash: old_token_hash,
+                        session_id,
+                    })
+                    .execute(conn)
+                    .await?;
+            }
+
+            Ok(rotated)
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+/// Ends a session because one of its refresh tokens was used twice, which means someone else has a copy of it.
+///
+/// Returns the user the session belonged to, if it hadn't already ended.
+pub async fn revoke_token_family(
+    conn: &mut Connection,
+    session_id: Uuid,
+) -> Result<Option<Uuid>, APIError> {
+    warn!(session_id = %session_id, "refresh token reused, revoking session");
+
+    diesel::delete(refresh_tokens::table.find(session_id))
+        .returning(refresh_tokens::user_id)
+        .get_result(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)
+}
+
+/// Finds the sessions of a user that haven't expired, most recently used first.
+pub async fn find_sessions_for_user(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<Vec<RefreshToken>, APIError> {
+    RefreshToken::all()
+        .filter(refresh_tokens::user_id.eq(user_id))
+        .filter(refresh_tokens::expires_at.gt(now))
+        .order(refresh_tokens::last_used_at.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Ends one of a user's sessions. Returns whether the session existed.
+pub async fn delete_session(
+    conn: &mut Connection,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> Result<bool, APIError> {
+    let deleted = diesel::delete(
+        refresh_tokens::table
+            .filter(refresh_tokens::id.eq(session_id))
+            .filter(refresh_tokens::user_id.eq(user_id)),
+    )
+    .execute(conn)
+    .await
+    .map_err(APIError::from)?;
+
+    Ok(deleted > 0)
+}
+
+/// Ends every session of a user, except for `keep` if given. Returns the sessions that were ended.
+pub async fn delete_sessions(
+    conn: &mut Connection,
+    user_id: Uuid,
+    keep: Option<Uuid>,
+) -> Result<Vec<Uuid>, APIError> {
+    let mut query = diesel::delete(refresh_tokens::table)
+        .filter(refresh_tokens::user_id.eq(user_id))
+        .into_boxed();
+    if let Some(keep) = keep {
+        query = query.filter(refresh_tokens::id.ne(keep));
+    }
+
+    query
+        .returning(refresh_tokens::id)
+        .get_results(conn)
+        .await
+        .map_err(APIError::from)
 }
 
-pub async fn delete_refresh_token_if_exists(
+pub async fn update_password(
     conn: &mut Connection,
     user_id: Uuid,
+    hashed_password: String,
 ) -> Result<(), APIError> {
-    let token_exists: bool = select(exists(
-        refresh_tokens::table.filter(refresh_tokens::user_id.eq(user_id)),
-    ))
-    .get_result::<bool>(conn)
+    diesel::update(users::table.find(user_id))
+        .set(users::password.eq(hashed_password))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Creates a password reset token for a user, replacing any they already had. Returns the secret to send them.
+pub async fn create_password_reset_token(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<String, APIError> {
+    let token = generate_secret();
+    let reset_token = NewPasswordResetToken {
+        user_id,
+        token_hash: hash_secret(&token),
+        expires_at: chrono::Utc::now().naive_utc()
+            + chrono::Duration::hours(PASSWORD_RESET_LIFETIME_HOURS),
+    };
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            diesel::delete(
+                password_reset_tokens::table.filter(password_reset_tokens::user_id.eq(user_id)),
+            )
+            .execute(conn)
+            .await?;
+
+            diesel::insert_into(password_reset_tokens::table)
+                .values(&reset_token)
+                .execute(conn)
+                .await?;
+
+            Ok(())
+        }
+        .scope_boxed()
+    })
     .await
-    .map_err(|e| {
-        error!(error = %e, "failed to check if refresh token exists");
-        APIErrorBuilder::from_error(e).build()
-    })?;
+    .map_err(APIError::from)?;
+
+    Ok(token)
+}
+
+/// Uses up a password reset token to set a new password. Returns the user whose password was reset.
+pub async fn reset_password(
+    conn: &mut Connection,
+    token: &str,
+    hashed_password: String,
+) -> Result<Uuid, APIError> {
+    let token_hash = hash_secret(token);
+
+    let user_id = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                // Marking the token used in the same statement that checks it means it can only be used once.
+                let user_id = diesel::update(
+                    password_reset_tokens::table
+                        .filter(password_reset_tokens::token_hash.eq(token_hash))
+                        .filter(password_reset_tokens::used_at.is_null())
+                        .filter(password_reset_tokens::expires_at.gt(now)),
+                )
+                .set(password_reset_tokens::used_at.eq(now))
+                .returning(password_reset_tokens::user_id)
+                .get_result::<Uuid>(conn)
+                .await
+                .optional()?;
+
+                if let Some(user_id) = user_id {
+                    diesel::update(users::table.find(user_id))
+                        .set(users::password.eq(hashed_password))
+                        .execute(conn)
+                        .await?;
+                }
+
+                Ok(user_id)
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(APIError::from)?;
+
+    user_id.ok_or_else(|| APIErrorBuilder::new(InvalidPasswordResetToken).build())
+}
+
+pub fn password_reset_email(user: &User, token: &str, settings: &App) -> Email {
+    let link = format!(
+        "{}/reset-password?token={}",
+        settings.web_url.trim_end_matches('/'),
+        token
+    );
+
+    Email {
+        to: user.email.clone(),
+        subject: "Reset your Domus password".to_string(),
+        body: format!(
+            "Hi {},\n\n\
+            Someone asked to reset the password of your Domus account. Follow this link to choose a new one:\n\n\
+            {}\n\n\
+            The link can be used once and expires in {} hour. If you didn't ask for this, you can ignore this email.\n",
+            user.first_name, link, PASSWORD_RESET_LIFETIME_HOURS
+        ),
+    }
+}
+
+/// Creates a token to verify that `email` belongs to a user, replacing any they already had. Returns the secret
+/// to send to the address.
+pub async fn create_email_verification_token(
+    conn: &mut Connection,
+    user_id: Uuid,
+    email: &str,
+) -> Result<String, APIError> {
+    let token = generate_secret();
+    let verification_token = NewEmailVerificationToken {
+        user_id,
+        email: email.to_string(),
+        token_hash: hash_secret(&token),
+        expires_at: chrono::Utc::now().naive_utc()
+            + chrono::Duration::hours(EMAIL_VERIFICATION_LIFETIME_HOURS),
+    };
 
-    if token_exists {
-        diesel::delete(refresh_tokens::table.filter(refresh_tokens::user_id.eq(user_id)))
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            diesel::delete(
+                email_verification_tokens::table
+                    .filter(email_verification_tokens::user_id.eq(user_id)),
+            )
             .execute(conn)
-            .await
-            .map_err(|e| {
-                error!(error = %e, "failed to delete refresh token");
-                APIErrorBuilder::from_error(e).build()
-            })?;
+            .await?;
+
+            diesel::insert_into(email_verification_tokens::table)
+                .values(&verification_token)
+                .execute(conn)
+                .await?;
+
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)?;
+
+    Ok(token)
+}
+
+/// Stops a user from being sent verification emails too often.
+pub async fn check_verification_email_throttle(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    let last_sent = email_verification_tokens::table
+        .filter(email_verification_tokens::user_id.eq(user_id))
+        .select(diesel::dsl::max(email_verification_tokens::created_at))
+        .first::<Option<chrono::NaiveDateTime>>(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    let Some(last_sent) = last_sent else {
+        return Ok(());
+    };
+
+    let wait = last_sent + chrono::Duration::seconds(VERIFICATION_EMAIL_INTERVAL_SECONDS)
+        - chrono::Utc::now().naive_utc();
+    if wait > chrono::Duration::zero() {
+        return Err(APIErrorBuilder::new(VerificationEmailThrottled)
+            .with_field("retry_after", (wait.num_seconds() + 1).into())
+            .build());
     }
 
     Ok(())
 }
 
+/// Uses up an email verification token, making the address it was sent to the verified email of its user.
+/// Returns the user whose email was verified.
+pub async fn verify_email(conn: &mut Connection, token: &str) -> Result<Uuid, APIError> {
+    let token_hash = hash_secret(token);
+
+    let user_id = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                let verified = diesel::delete(
+                    email_verification_tokens::table
+                        .filter(email_verification_tokens::token_hash.eq(token_hash))
+                        .filter(email_verification_tokens::expires_at.gt(now)),
+                )
+                .returning((
+                    email_verification_tokens::user_id,
+                    email_verification_tokens::email,
+                ))
+                .get_result::<(Uuid, String)>(conn)
+                .await
+                .optional()?;
+
+                let Some((user_id, email)) = verified else {
+                    return Ok(None);
+                };
+
+                diesel::update(users::table.find(user_id))
+                    .set((users::email.eq(email), users::email_verified_at.eq(now)))
+                    .execute(conn)
+                    .await?;
+
+                Ok(Some(user_id))
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(APIError::from)?;
+
+    user_id.ok_or_else(|| APIErrorBuilder::new(InvalidEmailVerificationToken).build())
+}
+
+pub fn email_verification_email(
+    first_name: &str,
+    email: &str,
+    token: &str,
+    settings: &App,
+) -> Email {
+    let link = format!(
+        "{}/verify-email?token={}",
+        settings.web_url.trim_end_matches('/'),
+        token
+    );
+
+    Email {
+        to: email.to_string(),
+        subject: "Verify your email address for Domus".to_string(),
+        body: format!(
+            "Hi {},\n\n\
+            Follow this link to verify that this is your email address:\n\n\
+            {}\n\n\
+            The link expires in {} hours. If you didn't sign up for Domus, you can ignore this email.\n",
+            first_name, link, EMAIL_VERIFICATION_LIFETIME_HOURS
+        ),
+    }
+}
+
+/// Stops users who haven't verified their email from taking an action, if the settings say they need to.
+pub fn require_verified_email(
+    user: &CurrentUser,
+    settings: &Verification,
+    action: VerifiedAction,
+) -> Result<(), APIError> {
+    if user.email_verified || !settings.required_for.contains(&action) {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(EmailNotVerified).build())
+}
+
 pub async fn find_user_by_email(
     conn: &mut Connection,
     email: &str,
@@ -132,42 +565,46 @@ pub async fn find_user_by_email(
         .first(conn)
         .await
         .optional()
-        .map_err(|e| {
-            error!(error = %e, "failed to find user by email");
-            APIErrorBuilder::from_error(e).build()
-        })
+        .map_err(APIError::from)
 }
 
 pub async fn find_user_by_id(conn: &mut Connection, id: &Uuid) -> Result<User, APIError> {
-    User::all().find(id).first(conn).await.map_err(|e| {
-        error!(error = %e, "failed to find user by id");
-        APIErrorBuilder::from_error(e).build()
-    })
+    User::all()
+        .find(id)
+        .first(conn)
+        .await
+        .map_err(APIError::from)
 }
 
 pub async fn find_refresh_token(
     conn: &mut Connection,
     refresh_token: &str,
 ) -> Result<Option<RefreshToken>, APIError> {
-    let uuid = Uuid::parse_str(refresh_token).map_err(|e| {
-        warn!(error = %e, "failed to parse refresh token");
-        APIErrorBuilder::new(Unauthorized)
-            .cause(e)
-            .detail("The token you provided is invalid.")
-            .build()
-    })?;
-
-    let token: Option<RefreshToken> = refresh_tokens::table
-        .filter(refresh_tokens::id.eq(uuid))
-        .first::<RefreshToken>(conn)
+    let token: Option<RefreshToken> = RefreshToken::all()
+        .filter(refresh_tokens::token_hash.eq(hash_secret(refresh_token)))
+        .first(conn)
         .await
         .optional()
-        .map_err(|e| APIErrorBuilder::from_error(e).build())?;
+        .map_err(APIError::from)?;
 
     Ok(token)
 }
 
-fn generate_auth_token(user: &User, private_key: &str) -> Result<String, ClaimError> {
+/// Finds the session a refresh token belonged to, if it has already been rotated out.
+pub async fn find_used_refresh_token(
+    conn: &mut Connection,
+    refresh_token: &str,
+) -> Result<Option<Uuid>, APIError> {
+    used_refresh_tokens::table
+        .find(hash_secret(refresh_token))
+        .select(used_refresh_tokens::session_id)
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)
+}
+
+fn generate_auth_token(user: &User, session_id: Uuid, keys: &KeySet) -> Result<String, ClaimError> {
     let mut claims = Claims::new_expires_in(&TOKEN_EXPIRY_TIME)?;
     claims.issuer("domus-api.jacksonc.dev")?;
     claims.subject(user.id.as_hyphenated().to_string().as_str())?;
@@ -177,7 +614,9 @@ fn generate_auth_token(user: &User, private_key: &str) -> Result<String, ClaimEr
     claims.add_additional("email", user.email.as_str())?;
     claims.add_additional("first_name", user.first_name.as_str())?;
     claims.add_additional("last_name", user.last_name.as_str())?;
+    claims.add_additional("email_verified", user.email_verified_at.is_some())?;
+    claims.add_additional("sid", session_id.as_hyphenated().to_string())?;
 
-    let key = AsymmetricSecretKey::<V4>::try_from(private_key)?;
-    public::sign(&key, &claims, None, None)
+    let signing_key = keys.signing_key();
+    public::sign(&signing_key.key, &claims, Some(&signing_key.footer), None)
 }
@@ -0,0 +1,421 @@
+use super::models::{BillTemplateResponse, CreateBillTemplateRequest, UpdateBillTemplateRequest};
+use super::recurrence::BillRecurrence;
+use super::utils::{
+    create_template, delete_template as remove_template, existing_portions,
+    find_splits_for_templates, find_template, find_template_with_splits,
+    find_templates_for_household, generate_bills, has_departed_members, require_template_editor,
+    update_template,
+};
+use crate::api::bills::split::{build_portions, rescale_portions};
+use crate::api::error::ErrorType::Conflict;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::{
+    find_household_for_user, find_household_member_ids, parse_member_id,
+};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::ValidatedJson;
+use crate::db::bill_template::{BillTemplateChangeset, NewBillTemplate};
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::Utc;
+use tracing::info;
+
+/// Create a recurring bill
+///
+/// Bills are generated from the template as they fall due, starting with the start date.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/bills/templates",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateBillTemplateRequest
+    ),
+    responses(
+        (status = 201, description = "Created recurring bill successfully", body = BillTemplateResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_bill_template(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateBillTemplateRequest>,
+) -> Result<(StatusCode, Json<BillTemplateResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let payer_id = match payload.payer_id {
+        Some(ref payer_id) => parse_member_id(payer_id, &member_ids, "payer_id")?,
+        None => current_user.id,
+    };
+    let portions = build_portions(payload.amount, &payload.split, &member_ids)?;
+
+    let today = Utc::now().date_naive();
+    let start_date = payload.start_date.unwrap_or(today);
+    let (recurrence, recurrence_interval) = payload.recurrence.to_columns();
+
+    info!(user_id = %current_user.id, household_id = %household_id, "creating bill template");
+    let template = create_template(
+        &mut conn,
+        NewBillTemplate {
+            household_id,
+            created_by: Some(current_user.id),
+            payer_id,
+            description: payload.description,
+            amount: payload.amount,
+            currency: payload.currency,
+            split_method: payload.split.method,
+            recurrence,
+            recurrence_interval,
+            start_date,
+            next_due_date: start_date,
+        },
+        portions,
+    )
+    .await?;
+
+    // Don't make members wait for the scheduler if the first bill is already due.
+    generate_bills(&mut conn, template.id, today).await?;
+
+    let template = find_template_with_splits(&mut conn, household_id, template.id).await?;
+    Ok((StatusCode::CREATED, Json(template.into())))
+}
+
+/// List the recurring bills of a household
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/bills/templates",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [BillTemplateResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_bill_templates(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<BillTemplateResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let templates = find_templates_for_household(&mut conn, household_id).await?;
+    let splits = find_splits_for_templates(&mut conn, &templates).await?;
+
+    let response = templates
+        .into_iter()
+        .zip(splits)
+        .map(BillTemplateResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a recurring bill
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/bills/templates/{template_id}",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("template_id" = String, Path, description = "The recurring bill id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = BillTemplateResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or recurring bill not found", body = APIError),
+    )
+)]
+pub async fn get_bill_template(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, template_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<BillTemplateResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let template_id = parse_friendly_id(&template_id, ItemIdType::BillTemplate)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let template = find_template_with_splits(&mut conn, household_id, template_id).await?;
+    Ok((StatusCode::OK, Json(template.into())))
+}
+
+/// Update a recurring bill
+///
+/// Changes apply to bills generated from now on. Bills that were already generated are not changed.
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/bills/templates/{template_id}",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("template_id" = String, Path, description = "The recurring bill id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateBillTemplateRequest
+    ),
+    responses(
+        (status = 200, description = "Updated recurring bill successfully", body = BillTemplateResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to change the recurring bill", body = APIError),
+        (status = 404, description = "Household or recurring bill not found", body = APIError),
+    )
+)]
+pub async fn update_bill_template(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, template_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateBillTemplateRequest>,
+) -> Result<(StatusCode, Json<BillTemplateResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let template_id = parse_friendly_id(&template_id, ItemIdType::BillTemplate)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let (template, splits) =
+        find_template_with_splits(&mut conn, household_id, template_id).await?;
+    require_template_editor(&template, &membership, current_user.id)?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let payer_id = payload
+        .payer_id
+        .map(|payer_id| parse_member_id(&payer_id, &member_ids, "payer_id"))
+        .transpose()?;
+
+    let amount = payload.amount.unwrap_or(template.amount);
+    let portions = match &payload.split {
+        Some(split) => Some(build_portions(amount, split, &member_ids)?),
+        None if payload.amount.is_some() => {
+            let existing = existing_portions(&splits);
+            Some(rescale_portions(
+                amount,
+                template.split_method,
+                &existing,
+                &member_ids,
+            )?)
+        }
+        None => None,
+    };
+    let recurrence = payload.recurrence.map(BillRecurrence::to_columns);
+
+    let changes = BillTemplateChangeset {
+        payer_id,
+        description: payload.description,
+        amount: payload.amount,
+        currency: payload.currency,
+        split_method: payload.split.map(|split| split.method),
+        recurrence: recurrence.map(|(kind, _)| kind),
+        recurrence_interval: recurrence.map(|(_, interval)| interval),
+        ..Default::default()
+    };
+
+    if has_changes(&changes) {
+        info!(user_id = %current_user.id, template_id = %template_id, "updating bill template");
+        update_template(&mut conn, template_id, changes, portions).await?;
+    }
+
+    let template = find_template_with_splits(&mut conn, household_id, template_id).await?;
+    Ok((StatusCode::OK, Json(template.into())))
+}
+
+/// Delete a recurring bill
+///
+/// Bills that were already generated are kept.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/bills/templates/{template_id}",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("template_id" = String, Path, description = "The recurring bill id"),
+    ),
+    responses(
+        (status = 204, description = "Deleted recurring bill successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to delete the recurring bill", body = APIError),
+        (status = 404, description = "Household or recurring bill not found", body = APIError),
+    )
+)]
+pub async fn delete_bill_template(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, template_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let template_id = parse_friendly_id(&template_id, ItemIdType::BillTemplate)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let template = find_template(&mut conn, household_id, template_id).await?;
+    require_template_editor(&template, &membership, current_user.id)?;
+
+    info!(user_id = %current_user.id, template_id = %template_id, "deleting bill template");
+    remove_template(&mut conn, template_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Pause a recurring bill
+///
+/// No bills are generated while a recurring bill is paused.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/bills/templates/{template_id}/pause",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("template_id" = String, Path, description = "The recurring bill id"),
+    ),
+    responses(
+        (status = 200, description = "Paused recurring bill successfully", body = BillTemplateResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to change the recurring bill", body = APIError),
+        (status = 404, description = "Household or recurring bill not found", body = APIError),
+    )
+)]
+pub async fn pause_bill_template(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, template_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<BillTemplateResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let template_id = parse_friendly_id(&template_id, ItemIdType::BillTemplate)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let template = find_template(&mut conn, household_id, template_id).await?;
+    require_template_editor(&template, &membership, current_user.id)?;
+
+    if template.paused_at.is_none() {
+        info!(user_id = %current_user.id, template_id = %template_id, "pausing bill template");
+        let changes = BillTemplateChangeset {
+            paused_at: Some(Some(Utc::now().naive_utc())),
+            ..Default::default()
+        };
+        update_template(&mut conn, template_id, changes, None).await?;
+    }
+
+    let template = find_template_with_splits(&mut conn, household_id, template_id).await?;
+    Ok((StatusCode::OK, Json(template.into())))
+}
+
+/// Resume a recurring bill
+///
+/// Bills that fell due while the recurring bill was paused are skipped. A recurring bill that includes someone who has
+/// left the household can't be resumed until its payer and split are changed.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/bills/templates/{template_id}/resume",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("template_id" = String, Path, description = "The recurring bill id"),
+    ),
+    responses(
+        (status = 200, description = "Resumed recurring bill successfully", body = BillTemplateResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to change the recurring bill", body = APIError),
+        (status = 404, description = "Household or recurring bill not found", body = APIError),
+        (status = 409, description = "A member of the recurring bill has left the household", body = APIError),
+    )
+)]
+pub async fn resume_bill_template(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, template_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<BillTemplateResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let template_id = parse_friendly_id(&template_id, ItemIdType::BillTemplate)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let (template, splits) =
+        find_template_with_splits(&mut conn, household_id, template_id).await?;
+    require_template_editor(&template, &membership, current_user.id)?;
+
+    if template.paused_at.is_some() {
+        let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+        if has_departed_members(&template, &splits, &member_ids) {
+            return Err(APIErrorBuilder::new(Conflict)
+                .detail("Someone in this recurring bill has left the household. Change its payer or split before resuming it.")
+                .build());
+        }
+
+        let today = Utc::now().date_naive();
+        let next_due_date = BillRecurrence::from(&template).first_due_on_or_after(
+            template.start_date,
+            template.next_due_date,
+            today,
+        );
+
+        info!(user_id = %current_user.id, template_id = %template_id, "resuming bill template");
+        let changes = BillTemplateChangeset {
+            next_due_date: Some(next_due_date),
+            paused_at: Some(None),
+            failure_count: Some(0),
+            ..Default::default()
+        };
+        update_template(&mut conn, template_id, changes, None).await?;
+        generate_bills(&mut conn, template_id, today).await?;
+    }
+
+    let template = find_template_with_splits(&mut conn, household_id, template_id).await?;
+    Ok((StatusCode::OK, Json(template.into())))
+}
+
+fn has_changes(changes: &BillTemplateChangeset) -> bool {
+    changes.payer_id.is_some()
+        || changes.description.is_some()
+        || changes.amount.is_some()
+        || changes.currency.is_some()
+        || changes.split_method.is_some()
+        || changes.recurrence.is_some()
+        || changes.recurrence_interval.is_some()
+        || changes.next_due_date.is_some()
+        || changes.paused_at.is_some()
+        || changes.failure_count.is_some()
+}
@@ -0,0 +1,28 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{get, post};
+use axum::{middleware, Router};
+use controllers::{
+    create_bill_template, delete_bill_template, get_bill_template, list_bill_templates,
+    pause_bill_template, resume_bill_template, update_bill_template,
+};
+
+pub mod controllers;
+pub mod models;
+pub mod recurrence;
+pub mod scheduler;
+mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_bill_template).get(list_bill_templates))
+        .route(
+            "/:template_id",
+            get(get_bill_template)
+                .patch(update_bill_template)
+                .delete(delete_bill_template),
+        )
+        .route("/:template_id/pause", post(pause_bill_template))
+        .route("/:template_id/resume", post(resume_bill_template))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
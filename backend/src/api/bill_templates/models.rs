@@ -0,0 +1,127 @@
+use super::recurrence::{validate_bill_recurrence, BillRecurrence};
+use crate::api::bills::currency::validate_currency;
+use crate::api::bills::models::{BillSplitResponse, SplitRequest};
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::bill::SplitMethod;
+use crate::db::bill_template::{BillTemplate, BillTemplateSplit};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateBillTemplateRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Rent", min_length = 1, max_length = 128)]
+    pub description: String,
+
+    /// The amount of each bill, in the minor unit of the currency (e.g. cents).
+    #[validate(range(min = 1))]
+    #[schema(example = 120_000, minimum = 1)]
+    pub amount: i64,
+
+    /// An ISO 4217 currency code.
+    #[validate(custom = "validate_currency")]
+    #[schema(example = "NZD", min_length = 3, max_length = 3)]
+    pub currency: String,
+
+    /// The member who pays each bill. Defaults to the current user.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: Option<String>,
+
+    #[validate]
+    pub split: SplitRequest,
+
+    #[validate(custom = "validate_bill_recurrence")]
+    pub recurrence: BillRecurrence,
+
+    /// The due date of the first bill. Defaults to today.
+    pub start_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateBillTemplateRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Rent", min_length = 1, max_length = 128)]
+    pub description: Option<String>,
+
+    /// The amount of each bill, in the minor unit of the currency (e.g. cents).
+    ///
+    /// If the split is not also given, the existing split is applied to the new amount. That isn't possible for
+    /// splits by exact amounts, or when a member in the split has left the household.
+    #[validate(range(min = 1))]
+    #[schema(example = 120_000, minimum = 1)]
+    pub amount: Option<i64>,
+
+    /// An ISO 4217 currency code.
+    #[validate(custom = "validate_currency")]
+    #[schema(example = "NZD", min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: Option<String>,
+
+    #[validate]
+    pub split: Option<SplitRequest>,
+
+    /// Changes how often bills are due, starting after the next bill.
+    #[validate(custom = "validate_bill_recurrence")]
+    pub recurrence: Option<BillRecurrence>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BillTemplateResponse {
+    #[schema(example = "bill_template|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    pub description: String,
+    #[schema(example = 120_000)]
+    pub amount: i64,
+    #[schema(example = "NZD")]
+    pub currency: String,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: String,
+    pub split_method: SplitMethod,
+    pub splits: Vec<BillSplitResponse>,
+    pub recurrence: BillRecurrence,
+    pub start_date: chrono::NaiveDate,
+    /// The due date of the next bill that will be generated.
+    pub next_due_date: chrono::NaiveDate,
+    /// Paused templates don't generate any bills.
+    pub paused: bool,
+    pub paused_at: Option<chrono::NaiveDateTime>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<(BillTemplate, Vec<BillTemplateSplit>)> for BillTemplateResponse {
+    fn from((template, splits): (BillTemplate, Vec<BillTemplateSplit>)) -> Self {
+        Self {
+            id: template.id.to_friendly_id(ItemIdType::BillTemplate),
+            household_id: template.household_id.to_friendly_id(ItemIdType::Household),
+            description: template.description.clone(),
+            amount: template.amount,
+            currency: template.currency.clone(),
+            payer_id: template.payer_id.to_friendly_id(ItemIdType::User),
+            split_method: template.split_method,
+            splits: splits
+                .into_iter()
+                .map(|split| BillSplitResponse {
+                    member_id: split.user_id.to_friendly_id(ItemIdType::User),
+                    amount: split.amount,
+                    value: split.value,
+                })
+                .collect(),
+            recurrence: BillRecurrence::from(&template),
+            start_date: template.start_date,
+            next_due_date: template.next_due_date,
+            paused: template.paused_at.is_some(),
+            paused_at: template.paused_at,
+            created_by: template
+                .created_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            created_at: template.created_at,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::db::bill_template::{BillRecurrenceKind, BillTemplate};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::ValidationError;
+
+/// How often a recurring bill is due.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BillRecurrence {
+    /// Every `weeks` weeks, on the same day of the week as the start date.
+    Weekly {
+        #[serde(default = "default_interval")]
+        weeks: u32,
+    },
+    /// Every `months` months, on the same day of the month as the start date. In shorter months
+    /// the bill is due on the last day of the month instead.
+    Monthly {
+        #[serde(default = "default_interval")]
+        months: u32,
+    },
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+impl BillRecurrence {
+    /// Converts the recurrence into the `(recurrence, recurrence_interval)` columns of a template.
+    pub fn to_columns(self) -> (BillRecurrenceKind, i32) {
+        let (kind, interval) = match self {
+            BillRecurrence::Weekly { weeks } => (BillRecurrenceKind::Weekly, weeks),
+            BillRecurrence::Monthly { months } => (BillRecurrenceKind::Monthly, months),
+        };
+        (kind, i32::try_from(interval).unwrap_or(i32::MAX))
+    }
+
+    /// The due date of the bill that follows one due on `previous`, for a template starting on `start`.
+    pub fn next_due_date(self, start: NaiveDate, previous: NaiveDate) -> NaiveDate {
+        match self {
+            BillRecurrence::Weekly { weeks } => previous + Duration::weeks(weeks.max(1).into()),
+            BillRecurrence::Monthly { months } => {
+                let month = previous
+                    .with_day(1)
+                    .and_then(|first| first.checked_add_months(Months::new(months.max(1))));
+                let Some(month) = month else {
+                    return NaiveDate::MAX;
+                };
+
+                let days_in_month = month
+                    .checked_add_months(Months::new(1))
+                    .map(|next| (next - month).num_days() as u32)
+                    .unwrap_or(31);
+                month
+                    .with_day(start.day().min(days_in_month))
+                    .unwrap_or(month)
+            }
+        }
+    }
+
+    /// The first due date on or after `date`, counting on from a bill due on `previous`.
+    pub fn first_due_on_or_after(
+        self,
+        start: NaiveDate,
+        previous: NaiveDate,
+        date: NaiveDate,
+    ) -> NaiveDate {
+        let mut due_date = previous;
+        while due_date < date {
+            due_date = self.next_due_date(start, due_date);
+        }
+        due_date
+    }
+}
+
+impl From<&BillTemplate> for BillRecurrence {
+    fn from(template: &BillTemplate) -> Self {
+        let interval = u32::try_from(template.recurrence_interval).unwrap_or(1);
+        match template.recurrence {
+            BillRecurrenceKind::Weekly => BillRecurrence::Weekly { weeks: interval },
+            BillRecurrenceKind::Monthly => BillRecurrence::Monthly { months: interval },
+        }
+    }
+}
+
+pub fn validate_bill_recurrence(recurrence: &BillRecurrence) -> Result<(), ValidationError> {
+    match recurrence {
+        BillRecurrence::Weekly { weeks } if *weeks == 0 || *weeks > 52 => {
+            Err(ValidationError::new("range"))
+        }
+        BillRecurrence::Monthly { months } if *months == 0 || *months > 12 => {
+            Err(ValidationError::new("range"))
+        }
+        _ => Ok(()),
+    }
+}
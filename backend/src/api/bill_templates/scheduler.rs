@@ -0,0 +1,54 @@
+use super::utils::{find_due_template_ids, generate_bills, record_generation_failure};
+use crate::api::error::APIError;
+use crate::api::utils::db::get_db_connection;
+use crate::AppState;
+use chrono::{NaiveDate, Utc};
+use std::time::Duration;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+/// Generates bills from recurring bill templates as they fall due, until the server shuts down.
+pub async fn run(state: AppState) {
+    let period = Duration::from_secs(state.settings.scheduler.interval_seconds.max(1));
+    let mut interval = time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let today = Utc::now().date_naive();
+        match generate_due_bills(&state, today).await {
+            Ok(0) => {}
+            Ok(count) => info!(count, "generated recurring bills"),
+            Err(e) => error!(error = ?e, "failed to generate recurring bills"),
+        }
+    }
+}
+
+/// Generates the bills of every active template that are due on or before `today`.
+///
+/// A template that fails is logged and skipped so it doesn't hold up the others, and is paused once it has failed
+/// too many times in a row.
+async fn generate_due_bills(state: &AppState, today: NaiveDate) -> Result<usize, APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+
+    let mut generated = 0;
+    for template_id in find_due_template_ids(&mut conn, today).await? {
+        match generate_bills(&mut conn, template_id, today).await {
+            Ok(count) => generated += count,
+            Err(e) => match record_generation_failure(&mut conn, template_id).await {
+                Ok(false) => {
+                    warn!(error = ?e, template_id = %template_id, "failed to generate bills from template")
+                }
+                Ok(true) => {
+                    error!(error = ?e, template_id = %template_id, "paused bill template after repeated failures")
+                }
+                Err(record_error) => {
+                    error!(error = ?record_error, template_id = %template_id, "failed to record bill template failure")
+                }
+            },
+        }
+    }
+
+    Ok(generated)
+}
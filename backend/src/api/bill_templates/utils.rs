@@ -0,0 +1,352 @@
+use super::recurrence::BillRecurrence;
+use crate::api::bills::split::Portion;
+use crate::api::bills::utils::insert_splits;
+use crate::api::error::ErrorType::{BillTemplateNotFound, Forbidden};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::db::bill::{Bill, BillStatus, NewBill};
+use crate::db::bill_template::{
+    BillTemplate, BillTemplateChangeset, BillTemplateSplit, NewBillTemplate,
+};
+use crate::db::database::Connection;
+use crate::db::household::HouseholdMember;
+use crate::db::schema::{bill_template_splits, bill_templates, bills, household_members};
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::warn;
+use uuid::Uuid;
+
+/// The most bills a single template can catch up on in one go, e.g. after the server was down
+/// for a while. Any more are generated the next time the scheduler runs.
+const MAX_BILLS_PER_RUN: usize = 12;
+
+/// How many times in a row generating a template's bills can fail before the template is paused.
+const MAX_GENERATION_FAILURES: i32 = 5;
+
+/// Creates a recurring bill along with the portion each member owes.
+pub async fn create_template(
+    conn: &mut Connection,
+    template: NewBillTemplate,
+    portions: Vec<Portion>,
+) -> Result<BillTemplate, APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let template = diesel::insert_into(bill_templates::table)
+                .values(&template)
+                .returning(BillTemplate::as_returning())
+                .get_result(conn)
+                .await?;
+
+            insert_template_splits(conn, template.id, portions).await?;
+
+            Ok(template)
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+async fn insert_template_splits(
+    conn: &mut Connection,
+    template_id: Uuid,
+    portions: Vec<Portion>,
+) -> Result<Vec<BillTemplateSplit>, diesel::result::Error> {
+    let splits: Vec<BillTemplateSplit> = portions
+        .into_iter()
+        .map(|portion| BillTemplateSplit {
+            template_id,
+            user_id: portion.user_id,
+            amount: portion.amount,
+            value: portion.value,
+        })
+        .collect();
+
+    diesel::insert_into(bill_template_splits::table)
+        .values(&splits)
+        .returning(BillTemplateSplit::as_returning())
+        .get_results(conn)
+        .await
+}
+
+pub async fn find_template(
+    conn: &mut Connection,
+    household_id: Uuid,
+    template_id: Uuid,
+) -> Result<BillTemplate, APIError> {
+    BillTemplate::all()
+        .filter(bill_templates::id.eq(template_id))
+        .filter(bill_templates::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(BillTemplateNotFound).build())
+}
+
+pub async fn find_templates_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<BillTemplate>, APIError> {
+    BillTemplate::all()
+        .filter(bill_templates::household_id.eq(household_id))
+        .order(bill_templates::created_at.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the splits of the given templates, grouped by template in the same order.
+pub async fn find_splits_for_templates(
+    conn: &mut Connection,
+    templates: &[BillTemplate],
+) -> Result<Vec<Vec<BillTemplateSplit>>, APIError> {
+    let splits = BillTemplateSplit::belonging_to(templates)
+        .select(BillTemplateSplit::as_select())
+        .order(bill_template_splits::amount.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(splits.grouped_by(templates))
+}
+
+/// Finds a template with its splits.
+pub async fn find_template_with_splits(
+    conn: &mut Connection,
+    household_id: Uuid,
+    template_id: Uuid,
+) -> Result<(BillTemplate, Vec<BillTemplateSplit>), APIError> {
+    let template = find_template(conn, household_id, template_id).await?;
+    let splits = find_splits_for_templates(conn, std::slice::from_ref(&template))
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    Ok((template, splits))
+}
+
+/// Updates a template, replacing its splits if new portions are given.
+pub async fn update_template(
+    conn: &mut Connection,
+    template_id: Uuid,
+    changes: BillTemplateChangeset,
+    portions: Option<Vec<Portion>>,
+) -> Result<BillTemplate, APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let template = diesel::update(bill_templates::table.find(template_id))
+                .set(&changes)
+                .returning(BillTemplate::as_returning())
+                .get_result(conn)
+                .await?;
+
+            if let Some(portions) = portions {
+                diesel::delete(
+                    bill_template_splits::table
+                        .filter(bill_template_splits::template_id.eq(template_id)),
+                )
+                .execute(conn)
+                .await?;
+                insert_template_splits(conn, template_id, portions).await?;
+            }
+
+            Ok(template)
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+pub async fn delete_template(conn: &mut Connection, template_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(bill_templates::table.find(template_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Finds the active templates with a bill due on or before `today`.
+pub async fn find_due_template_ids(
+    conn: &mut Connection,
+    today: NaiveDate,
+) -> Result<Vec<Uuid>, APIError> {
+    bill_templates::table
+        .filter(bill_templates::paused_at.is_null())
+        .filter(bill_templates::next_due_date.le(today))
+        .select(bill_templates::id)
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Generates the bills of a template that are due on or before `today`, returning how many were created.
+///
+/// The template is locked while its bills are generated, and a template can only have one bill per due date, so
+/// running this more than once (or from more than one server) never creates duplicate bills. A template whose payer
+/// or split includes someone who has left the household is paused instead, until its split is fixed.
+pub async fn generate_bills(
+    conn: &mut Connection,
+    template_id: Uuid,
+    today: NaiveDate,
+) -> Result<usize, APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let template = BillTemplate::all()
+                .filter(bill_templates::id.eq(template_id))
+                .filter(bill_templates::paused_at.is_null())
+                .for_update()
+                .first(conn)
+                .await
+                .optional()?;
+            let Some(template) = template else {
+                return Ok(0);
+            };
+
+            let splits: Vec<BillTemplateSplit> = BillTemplateSplit::all()
+                .filter(bill_template_splits::template_id.eq(template_id))
+                .load(conn)
+                .await?;
+
+            let member_ids: Vec<Uuid> = household_members::table
+                .filter(household_members::household_id.eq(template.household_id))
+                .select(household_members::user_id)
+                .load(conn)
+                .await?;
+            if has_departed_members(&template, &splits, &member_ids) {
+                warn!(template_id = %template_id, "pausing bill template because a member in it has left the household");
+                diesel::update(bill_templates::table.find(template_id))
+                    .set(bill_templates::paused_at.eq(Utc::now().naive_utc()))
+                    .execute(conn)
+                    .await?;
+                return Ok(0);
+            }
+
+            let recurrence = BillRecurrence::from(&template);
+            let mut due_date = template.next_due_date;
+            let mut generated = 0;
+            let mut attempts = 0;
+            while due_date <= today && attempts < MAX_BILLS_PER_RUN {
+                let bill = diesel::insert_into(bills::table)
+                    .values(NewBill {
+                        household_id: template.household_id,
+                        created_by: template.created_by,
+                        payer_id: template.payer_id,
+                        description: template.description.clone(),
+                        amount: template.amount,
+                        currency: template.currency.clone(),
+                        due_date: Some(due_date),
+                        split_method: template.split_method,
+                        template_id: Some(template.id),
+                        status: BillStatus::Posted,
+                    })
+                    .on_conflict((bills::template_id, bills::due_date))
+                    .do_nothing()
+                    .returning(Bill::as_returning())
+                    .get_result(conn)
+                    .await
+                    .optional()?;
+
+                if let Some(bill) = bill {
+                    let portions = splits
+                        .iter()
+                        .map(|split| Portion {
+                            user_id: split.user_id,
+                            amount: split.amount,
+                            value: split.value,
+                        })
+                        .collect();
+                    insert_splits(conn, bill.id, portions).await?;
+                    generated += 1;
+                }
+
+                due_date = recurrence.next_due_date(template.start_date, due_date);
+                attempts += 1;
+            }
+
+            diesel::update(bill_templates::table.find(template_id))
+                .set((
+                    bill_templates::next_due_date.eq(due_date),
+                    bill_templates::failure_count.eq(0),
+                ))
+                .execute(conn)
+                .await?;
+
+            Ok(generated)
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+/// Whether the payer or anyone in the split of a template is no longer a member of its household.
+pub fn has_departed_members(
+    template: &BillTemplate,
+    splits: &[BillTemplateSplit],
+    member_ids: &[Uuid],
+) -> bool {
+    !member_ids.contains(&template.payer_id)
+        || splits
+            .iter()
+            .any(|split| !member_ids.contains(&split.user_id))
+}
+
+/// Records that generating the bills of a template failed, pausing the template once it has failed
+/// `MAX_GENERATION_FAILURES` times in a row. Returns whether the template was paused.
+pub async fn record_generation_failure(
+    conn: &mut Connection,
+    template_id: Uuid,
+) -> Result<bool, APIError> {
+    let failures: i32 = diesel::update(bill_templates::table.find(template_id))
+        .set(bill_templates::failure_count.eq(bill_templates::failure_count + 1))
+        .returning(bill_templates::failure_count)
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)?;
+    if failures < MAX_GENERATION_FAILURES {
+        return Ok(false);
+    }
+
+    diesel::update(bill_templates::table.find(template_id))
+        .filter(bill_templates::paused_at.is_null())
+        .set(bill_templates::paused_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(true)
+}
+
+/// Ensures the current user can change a template. Owners, admins, the payer and the creator of a template can.
+pub fn require_template_editor(
+    template: &BillTemplate,
+    membership: &HouseholdMember,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    if membership.role.can_manage()
+        || template.payer_id == user_id
+        || template.created_by == Some(user_id)
+    {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(Forbidden)
+        .detail("Only owners, admins, the payer and the creator of a recurring bill can change it.")
+        .build())
+}
+
+/// Turns the saved splits of a template back into portions, so they can be applied to a new amount.
+pub fn existing_portions(splits: &[BillTemplateSplit]) -> Vec<Portion> {
+    splits
+        .iter()
+        .map(|split| Portion {
+            user_id: split.user_id,
+            amount: split.amount,
+            value: split.value,
+        })
+        .collect()
+}
@@ -0,0 +1,374 @@
+use super::models::{BillResponse, CreateBillRequest, PostBillRequest, UpdateBillRequest};
+use super::split::{build_portions, rescale_portions};
+use super::utils::{
+    create_bill as insert_bill, delete_bill as remove_bill, existing_portions, find_bill,
+    find_bills_for_household, find_splits_for_bills, load_bill_details, load_details,
+    require_bill_editor, update_bill as save_bill,
+};
+use crate::api::error::ErrorType::BillAlreadyPosted;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::{
+    find_household_for_user, find_household_member_ids, parse_member_id,
+};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::{field_error, ValidatedJson};
+use crate::db::bill::{BillChangeset, BillStatus, NewBill};
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use tracing::info;
+
+/// Create a new bill
+///
+/// The amount is split between the given members according to the split method.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/bills",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateBillRequest
+    ),
+    responses(
+        (status = 201, description = "Created new bill successfully", body = BillResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_bill(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateBillRequest>,
+) -> Result<(StatusCode, Json<BillResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let payer_id = match payload.payer_id {
+        Some(ref payer_id) => parse_member_id(payer_id, &member_ids, "payer_id")?,
+        None => current_user.id,
+    };
+    let portions = build_portions(payload.amount, &payload.split, &member_ids)?;
+
+    info!(user_id = %current_user.id, household_id = %household_id, "creating bill");
+    let bill = insert_bill(
+        &mut conn,
+        NewBill {
+            household_id,
+            created_by: Some(current_user.id),
+            payer_id,
+            description: payload.description,
+            amount: payload.amount,
+            currency: payload.currency,
+            due_date: payload.due_date,
+            split_method: payload.split.method,
+            template_id: None,
+            status: BillStatus::Posted,
+        },
+        portions,
+    )
+    .await?;
+    let details = load_bill_details(&mut conn, bill).await?;
+
+    Ok((StatusCode::CREATED, Json(details.into())))
+}
+
+/// List the bills of a household
+///
+/// Bills are returned newest first.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/bills",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [BillResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_bills(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<BillResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let bills = find_bills_for_household(&mut conn, household_id).await?;
+    let response = load_details(&mut conn, bills)
+        .await?
+        .into_iter()
+        .map(BillResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a bill
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/bills/{bill_id}",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("bill_id" = String, Path, description = "The bill id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = BillResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or bill not found", body = APIError),
+    )
+)]
+pub async fn get_bill(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, bill_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<BillResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let bill_id = parse_friendly_id(&bill_id, ItemIdType::Bill)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let bill = find_bill(&mut conn, household_id, bill_id).await?;
+    let details = load_bill_details(&mut conn, bill).await?;
+
+    Ok((StatusCode::OK, Json(details.into())))
+}
+
+/// Update a bill
+///
+/// Only owners, admins, the payer and the creator of a bill can update it.
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/bills/{bill_id}",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("bill_id" = String, Path, description = "The bill id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateBillRequest
+    ),
+    responses(
+        (status = 200, description = "Updated bill successfully", body = BillResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to change the bill", body = APIError),
+        (status = 404, description = "Household or bill not found", body = APIError),
+    )
+)]
+pub async fn update_bill(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, bill_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateBillRequest>,
+) -> Result<(StatusCode, Json<BillResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let bill_id = parse_friendly_id(&bill_id, ItemIdType::Bill)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let bill = find_bill(&mut conn, household_id, bill_id).await?;
+    require_bill_editor(&bill, &membership, current_user.id)?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let payer_id = payload
+        .payer_id
+        .map(|payer_id| parse_member_id(&payer_id, &member_ids, "payer_id"))
+        .transpose()?;
+
+    let amount = payload.amount.unwrap_or(bill.amount);
+    let portions = match &payload.split {
+        Some(split) => Some(build_portions(amount, split, &member_ids)?),
+        None if payload.amount.is_some() => {
+            let splits = find_splits_for_bills(&mut conn, std::slice::from_ref(&bill))
+                .await?
+                .pop()
+                .unwrap_or_default();
+            let existing = existing_portions(&splits);
+            Some(rescale_portions(
+                amount,
+                bill.split_method,
+                &existing,
+                &member_ids,
+            )?)
+        }
+        None => None,
+    };
+
+    let changes = BillChangeset {
+        payer_id,
+        description: payload.description,
+        amount: payload.amount,
+        currency: payload.currency,
+        due_date: payload.due_date.map(Some),
+        split_method: payload.split.map(|split| split.method),
+        ..Default::default()
+    };
+
+    let bill = if has_changes(&changes) {
+        info!(user_id = %current_user.id, bill_id = %bill_id, "updating bill");
+        save_bill(&mut conn, bill_id, changes, portions).await?
+    } else {
+        bill
+    };
+    let details = load_bill_details(&mut conn, bill).await?;
+
+    Ok((StatusCode::OK, Json(details.into())))
+}
+
+/// Post a draft bill
+///
+/// Splits a draft bill between members so it counts towards their balances.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/bills/{bill_id}/post",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("bill_id" = String, Path, description = "The bill id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = PostBillRequest
+    ),
+    responses(
+        (status = 200, description = "Posted bill successfully", body = BillResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to change the bill", body = APIError),
+        (status = 404, description = "Household or bill not found", body = APIError),
+        (status = 409, description = "Bill has already been posted", body = APIError),
+    )
+)]
+pub async fn post_bill(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, bill_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<PostBillRequest>,
+) -> Result<(StatusCode, Json<BillResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let bill_id = parse_friendly_id(&bill_id, ItemIdType::Bill)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let bill = find_bill(&mut conn, household_id, bill_id).await?;
+    require_bill_editor(&bill, &membership, current_user.id)?;
+
+    if bill.status == BillStatus::Posted {
+        return Err(APIErrorBuilder::new(BillAlreadyPosted).build());
+    }
+
+    let amount = payload.amount.unwrap_or(bill.amount);
+    if amount <= 0 {
+        return Err(field_error(
+            "amount",
+            "required",
+            "The bill needs an amount before it can be posted.",
+        ));
+    }
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let payer_id = payload
+        .payer_id
+        .map(|payer_id| parse_member_id(&payer_id, &member_ids, "payer_id"))
+        .transpose()?;
+    let portions = build_portions(amount, &payload.split, &member_ids)?;
+
+    let changes = BillChangeset {
+        payer_id,
+        amount: Some(amount),
+        split_method: Some(payload.split.method),
+        status: Some(BillStatus::Posted),
+        ..Default::default()
+    };
+
+    info!(user_id = %current_user.id, bill_id = %bill_id, "posting bill");
+    let bill = save_bill(&mut conn, bill_id, changes, Some(portions)).await?;
+    let details = load_bill_details(&mut conn, bill).await?;
+
+    Ok((StatusCode::OK, Json(details.into())))
+}
+
+/// Delete a bill
+///
+/// Only owners, admins, the payer and the creator of a bill can delete it.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/bills/{bill_id}",
+    tag = "bills",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("bill_id" = String, Path, description = "The bill id"),
+    ),
+    responses(
+        (status = 204, description = "Deleted bill successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to delete the bill", body = APIError),
+        (status = 404, description = "Household or bill not found", body = APIError),
+    )
+)]
+pub async fn delete_bill(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, bill_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let bill_id = parse_friendly_id(&bill_id, ItemIdType::Bill)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let bill = find_bill(&mut conn, household_id, bill_id).await?;
+    require_bill_editor(&bill, &membership, current_user.id)?;
+
+    info!(user_id = %current_user.id, bill_id = %bill_id, "deleting bill");
+    remove_bill(&mut conn, bill_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn has_changes(changes: &BillChangeset) -> bool {
+    changes.payer_id.is_some()
+        || changes.description.is_some()
+        || changes.amount.is_some()
+        || changes.currency.is_some()
+        || changes.due_date.is_some()
+        || changes.split_method.is_some()
+        || changes.status.is_some()
+}
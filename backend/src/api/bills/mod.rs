@@ -0,0 +1,22 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{get, post};
+use axum::{middleware, Router};
+use controllers::{create_bill, delete_bill, get_bill, list_bills, post_bill, update_bill};
+
+pub mod controllers;
+pub(crate) mod currency;
+pub mod models;
+pub(crate) mod split;
+pub(crate) mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_bill).get(list_bills))
+        .route(
+            "/:bill_id",
+            get(get_bill).patch(update_bill).delete(delete_bill),
+        )
+        .route("/:bill_id/post", post(post_bill))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
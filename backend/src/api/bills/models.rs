@@ -0,0 +1,204 @@
+use super::currency::validate_currency;
+use super::utils::BillDetails;
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::bill::{BillItem, BillSplit, BillStatus, SplitMethod};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateBillRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Power - October", min_length = 1, max_length = 128)]
+    pub description: String,
+
+    /// The total amount of the bill, in the minor unit of the currency (e.g. cents).
+    #[validate(range(min = 1))]
+    #[schema(example = 12_345, minimum = 1)]
+    pub amount: i64,
+
+    /// An ISO 4217 currency code.
+    #[validate(custom = "validate_currency")]
+    #[schema(example = "NZD", min_length = 3, max_length = 3)]
+    pub currency: String,
+
+    pub due_date: Option<chrono::NaiveDate>,
+
+    /// The member who paid the bill. Defaults to the current user.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: Option<String>,
+
+    #[validate]
+    pub split: SplitRequest,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateBillRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Power - October", min_length = 1, max_length = 128)]
+    pub description: Option<String>,
+
+    /// The total amount of the bill, in the minor unit of the currency (e.g. cents).
+    ///
+    /// If the split is not also given, the existing split is applied to the new amount. That isn't possible for
+    /// splits by exact amounts, or when a member in the split has left the household.
+    #[validate(range(min = 1))]
+    #[schema(example = 12_345, minimum = 1)]
+    pub amount: Option<i64>,
+
+    /// An ISO 4217 currency code.
+    #[validate(custom = "validate_currency")]
+    #[schema(example = "NZD", min_length = 3, max_length = 3)]
+    pub currency: Option<String>,
+
+    pub due_date: Option<chrono::NaiveDate>,
+
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: Option<String>,
+
+    #[validate]
+    pub split: Option<SplitRequest>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct PostBillRequest {
+    /// The total amount of the bill, in the minor unit of the currency (e.g. cents).
+    ///
+    /// Required if the draft doesn't have an amount yet.
+    #[validate(range(min = 1))]
+    #[schema(example = 12_345, minimum = 1)]
+    pub amount: Option<i64>,
+
+    /// The member who paid the bill. Defaults to the payer of the draft.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: Option<String>,
+
+    #[validate]
+    pub split: SplitRequest,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct SplitRequest {
+    pub method: SplitMethod,
+
+    /// The members sharing the bill.
+    #[validate(length(min = 1))]
+    #[validate]
+    pub portions: Vec<SplitPortion>,
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema, Debug)]
+pub struct SplitPortion {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub member_id: String,
+
+    /// Minor units for `exact`, basis points for `percentage` and a number of shares for `shares`.
+    /// Ignored for `equal`.
+    #[validate(range(min = 0))]
+    #[serde(default)]
+    #[schema(example = 5000, minimum = 0)]
+    pub value: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BillResponse {
+    #[schema(example = "bill|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    pub description: String,
+    #[schema(example = 12_345)]
+    pub amount: i64,
+    #[schema(example = "NZD")]
+    pub currency: String,
+    pub due_date: Option<chrono::NaiveDate>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: String,
+    pub split_method: SplitMethod,
+    pub splits: Vec<BillSplitResponse>,
+    /// Draft bills don't count towards balances until they are posted.
+    pub status: BillStatus,
+    pub items: Vec<BillItemResponse>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: Option<String>,
+    /// The recurring bill this bill was generated from, if any.
+    #[schema(example = "bill_template|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub template_id: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<BillDetails> for BillResponse {
+    fn from((bill, splits, items): BillDetails) -> Self {
+        Self {
+            id: bill.id.to_friendly_id(ItemIdType::Bill),
+            household_id: bill.household_id.to_friendly_id(ItemIdType::Household),
+            description: bill.description,
+            amount: bill.amount,
+            currency: bill.currency,
+            due_date: bill.due_date,
+            payer_id: bill.payer_id.to_friendly_id(ItemIdType::User),
+            split_method: bill.split_method,
+            splits: splits.into_iter().map(BillSplitResponse::from).collect(),
+            status: bill.status,
+            items: items.into_iter().map(BillItemResponse::from).collect(),
+            created_by: bill
+                .created_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            template_id: bill
+                .template_id
+                .map(|id| id.to_friendly_id(ItemIdType::BillTemplate)),
+            created_at: bill.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BillSplitResponse {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub member_id: String,
+    /// The amount this member owes, in the minor unit of the bill's currency.
+    #[schema(example = 6_173)]
+    pub amount: i64,
+    /// The value given when the bill was split.
+    #[schema(example = 5000)]
+    pub value: i64,
+}
+
+impl From<BillSplit> for BillSplitResponse {
+    fn from(split: BillSplit) -> Self {
+        Self {
+            member_id: split.user_id.to_friendly_id(ItemIdType::User),
+            amount: split.amount,
+            value: split.value,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BillItemResponse {
+    #[schema(example = "Milk")]
+    pub name: String,
+    #[schema(example = 2.0)]
+    pub quantity: Option<f64>,
+    #[schema(example = "L")]
+    pub unit: Option<String>,
+    #[schema(example = "Dairy")]
+    pub category: Option<String>,
+    /// The shopping list item this line item was bought for, if it still exists.
+    #[schema(example = "shopping_item|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub shopping_item_id: Option<String>,
+}
+
+impl From<BillItem> for BillItemResponse {
+    fn from(item: BillItem) -> Self {
+        Self {
+            name: item.name,
+            quantity: item.quantity,
+            unit: item.unit,
+            category: item.category,
+            shopping_item_id: item
+                .shopping_item_id
+                .map(|id| id.to_friendly_id(ItemIdType::ShoppingItem)),
+        }
+    }
+}
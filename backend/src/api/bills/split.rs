@@ -0,0 +1,408 @@
+use super::models::SplitRequest;
+use crate::api::error::APIError;
+use crate::api::households::utils::parse_member_id;
+use crate::api::utils::validation::{field_error, new_validation_error};
+use crate::db::bill::SplitMethod;
+use std::collections::HashSet;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+/// Percentages are given in basis points, so a full bill is 100.00%.
+pub const FULL_PERCENTAGE: i64 = 10_000;
+
+/// Checks the portions of a split add up for a bill of the given amount.
+pub fn validate_split(amount: i64, split: &SplitRequest) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    let mut seen = HashSet::new();
+    if !split
+        .portions
+        .iter()
+        .all(|portion| seen.insert(&portion.member_id))
+    {
+        errors.add(
+            "split",
+            new_validation_error("unique", "Each member can only appear once in a split."),
+        );
+    }
+
+    // Summed in i128 so that large values cannot overflow.
+    let total: i128 = split
+        .portions
+        .iter()
+        .map(|portion| i128::from(portion.value))
+        .sum();
+    let largest = split
+        .portions
+        .iter()
+        .map(|portion| portion.value)
+        .max()
+        .unwrap_or_default();
+    match split.method {
+        SplitMethod::Equal => {}
+        SplitMethod::Exact if largest > amount => errors.add(
+            "split",
+            new_validation_error(
+                "split_portion",
+                "An exact amount cannot be more than the bill amount.",
+            ),
+        ),
+        SplitMethod::Exact if total != i128::from(amount) => errors.add(
+            "split",
+            new_validation_error(
+                "split_total",
+                "The exact amounts must add up to the bill amount.",
+            ),
+        ),
+        SplitMethod::Percentage if largest > FULL_PERCENTAGE => errors.add(
+            "split",
+            new_validation_error(
+                "split_portion",
+                "A percentage cannot be more than 100% (10000 basis points).",
+            ),
+        ),
+        SplitMethod::Percentage if total != i128::from(FULL_PERCENTAGE) => errors.add(
+            "split",
+            new_validation_error(
+                "split_total",
+                "The percentages must add up to 100% (10000 basis points).",
+            ),
+        ),
+        SplitMethod::Shares if split.portions.iter().any(|portion| portion.value == 0) => errors
+            .add(
+                "split",
+                new_validation_error(
+                    "split_shares",
+                    "Every member in a split by shares needs at least one share.",
+                ),
+            ),
+        _ => {}
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Works out how much each portion of a split owes.
+///
+/// The result is in the same order as `values`, which are interpreted according to `method` (equal splits use a
+/// value of 1 for everyone), and always adds up to `amount`.
+pub fn allocate_split(amount: i64, method: SplitMethod, values: &[i64]) -> Vec<i64> {
+    match method {
+        SplitMethod::Exact => values.to_vec(),
+        SplitMethod::Equal | SplitMethod::Percentage | SplitMethod::Shares => {
+            allocate(amount, values)
+        }
+    }
+}
+
+/// Divides `total` in proportion to `weights` using the largest remainder method.
+///
+/// Each part is rounded down, then the leftover minor units are handed out one at a time to the parts that lost the
+/// most to rounding. Ties go to the earlier part, so the result is deterministic.
+pub fn allocate(total: i64, weights: &[i64]) -> Vec<i64> {
+    let weight_sum: i128 = weights.iter().map(|weight| i128::from(*weight)).sum();
+    if weight_sum <= 0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut parts = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().enumerate() {
+        let exact = i128::from(total) * i128::from(*weight);
+        parts.push((exact / weight_sum) as i64);
+        remainders.push((exact % weight_sum, index));
+    }
+
+    let mut leftover = total - parts.iter().sum::<i64>();
+    remainders.sort_by(|(a, a_index), (b, b_index)| b.cmp(a).then(a_index.cmp(b_index)));
+    for (_, index) in remainders {
+        if leftover <= 0 {
+            break;
+        }
+        parts[index] += 1;
+        leftover -= 1;
+    }
+
+    parts
+}
+
+/// A member's part of a bill, before it has been saved.
+pub struct Portion {
+    pub user_id: Uuid,
+    pub amount: i64,
+    pub value: i64,
+}
+
+/// Validates a split and works out what each member owes.
+pub fn build_portions(
+    amount: i64,
+    split: &SplitRequest,
+    member_ids: &[Uuid],
+) -> Result<Vec<Portion>, APIError> {
+    validate_split(amount, split)?;
+
+    let user_ids = split
+        .portions
+        .iter()
+        .map(|portion| parse_member_id(&portion.member_id, member_ids, "split"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let values: Vec<i64> = match split.method {
+        SplitMethod::Equal => vec![1; split.portions.len()],
+        _ => split.portions.iter().map(|portion| portion.value).collect(),
+    };
+    let amounts = allocate_split(amount, split.method, &values);
+
+    Ok(user_ids
+        .into_iter()
+        .zip(amounts)
+        .zip(values)
+        .map(|((user_id, amount), value)| Portion {
+            user_id,
+            amount,
+            value,
+        })
+        .collect())
+}
+
+/// Works out the portions of an existing split again for a new bill amount.
+///
+/// Exact amounts can't be carried over to a different total, and a member who has left the household can't keep
+/// their part, so in either case the amount can only be changed along with a new split.
+pub fn rescale_portions(
+    amount: i64,
+    method: SplitMethod,
+    existing: &[Portion],
+    member_ids: &[Uuid],
+) -> Result<Vec<Portion>, APIError> {
+    if existing.is_empty() {
+        return Ok(Vec::new());
+    }
+    if method == SplitMethod::Exact {
+        return Err(field_error(
+            "split",
+            "required",
+            "An amount change requires a new split for bills split by exact amounts.",
+        ));
+    }
+    if existing
+        .iter()
+        .any(|portion| !member_ids.contains(&portion.user_id))
+    {
+        return Err(field_error(
+            "split",
+            "member_left",
+            "A member in the split has left the household, so an amount change requires a new split.",
+        ));
+    }
+
+    let values: Vec<i64> = existing.iter().map(|portion| portion.value).collect();
+    let amounts = allocate_split(amount, method, &values);
+
+    Ok(existing
+        .iter()
+        .zip(amounts)
+        .map(|(portion, amount)| Portion {
+            user_id: portion.user_id,
+            amount,
+            value: portion.value,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::bills::models::SplitPortion;
+
+    fn split(method: SplitMethod, values: &[i64]) -> SplitRequest {
+        SplitRequest {
+            method,
+            portions: values
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| SplitPortion {
+                    member_id: format!("user|{}", i),
+                    value,
+                })
+                .collect(),
+        }
+    }
+
+    fn error_codes(result: Result<(), ValidationErrors>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .field_errors()
+            .get("split")
+            .map(|errors| errors.iter().map(|error| error.code.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn exact_amounts_must_add_up_to_the_bill() {
+        assert!(validate_split(1000, &split(SplitMethod::Exact, &[400, 600])).is_ok());
+        assert_eq!(
+            error_codes(validate_split(
+                1000,
+                &split(SplitMethod::Exact, &[400, 500])
+            )),
+            vec!["split_total"]
+        );
+    }
+
+    #[test]
+    fn percentages_must_add_up_to_a_whole() {
+        assert!(validate_split(999, &split(SplitMethod::Percentage, &[2500, 7500])).is_ok());
+        assert_eq!(
+            error_codes(validate_split(
+                999,
+                &split(SplitMethod::Percentage, &[2500, 2500])
+            )),
+            vec!["split_total"]
+        );
+    }
+
+    #[test]
+    fn portions_cannot_be_larger_than_the_whole() {
+        assert_eq!(
+            error_codes(validate_split(1000, &split(SplitMethod::Exact, &[1001, 0]))),
+            vec!["split_portion"]
+        );
+        assert_eq!(
+            error_codes(validate_split(
+                1000,
+                &split(SplitMethod::Percentage, &[10_001, 0])
+            )),
+            vec!["split_portion"]
+        );
+    }
+
+    #[test]
+    fn huge_values_do_not_overflow() {
+        let values = [i64::MAX, i64::MAX, 2];
+
+        assert_eq!(
+            error_codes(validate_split(1000, &split(SplitMethod::Exact, &values))),
+            vec!["split_portion"]
+        );
+        assert!(validate_split(1000, &split(SplitMethod::Shares, &values)).is_ok());
+    }
+
+    #[test]
+    fn shares_need_at_least_one_share_each() {
+        assert_eq!(
+            error_codes(validate_split(1000, &split(SplitMethod::Shares, &[1, 0]))),
+            vec!["split_shares"]
+        );
+    }
+
+    #[test]
+    fn members_can_only_appear_once() {
+        let mut request = split(SplitMethod::Equal, &[0, 0]);
+        request.portions[1].member_id = request.portions[0].member_id.clone();
+
+        assert_eq!(error_codes(validate_split(1000, &request)), vec!["unique"]);
+    }
+
+    #[test]
+    fn allocate_always_adds_up_to_the_total() {
+        let parts = allocate(1000, &[1, 1, 1]);
+
+        assert_eq!(parts.iter().sum::<i64>(), 1000);
+        // The leftover minor unit goes to the earliest part.
+        assert_eq!(parts, vec![334, 333, 333]);
+    }
+
+    #[test]
+    fn allocate_hands_leftovers_to_the_largest_remainders() {
+        assert_eq!(allocate(100, &[3333, 3333, 3334]), vec![33, 33, 34]);
+        assert_eq!(allocate(10, &[1, 2]), vec![3, 7]);
+    }
+
+    #[test]
+    fn allocate_without_weights_is_all_zero() {
+        assert_eq!(allocate(1000, &[0, 0]), vec![0, 0]);
+        assert!(allocate(1000, &[]).is_empty());
+    }
+
+    #[test]
+    fn allocate_handles_huge_weights() {
+        let parts = allocate(i64::MAX, &[i64::MAX, i64::MAX]);
+
+        assert_eq!(
+            parts.iter().map(|&part| i128::from(part)).sum::<i128>(),
+            i128::from(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn exact_splits_are_taken_as_is() {
+        assert_eq!(
+            allocate_split(1000, SplitMethod::Exact, &[250, 750]),
+            vec![250, 750]
+        );
+    }
+
+    fn portions(user_ids: &[Uuid], values: &[i64]) -> Vec<Portion> {
+        user_ids
+            .iter()
+            .zip(values)
+            .map(|(&user_id, &value)| Portion {
+                user_id,
+                amount: 0,
+                value,
+            })
+            .collect()
+    }
+
+    fn field_error_code(error: APIError) -> String {
+        let error = serde_json::to_value(error).unwrap();
+        error["errors"]["split"][0]["code"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn rescaling_keeps_the_members_and_values() {
+        let members = [Uuid::new_v4(), Uuid::new_v4()];
+        let existing = portions(&members, &[1, 3]);
+
+        let rescaled = rescale_portions(2000, SplitMethod::Shares, &existing, &members).unwrap();
+
+        assert_eq!(
+            rescaled
+                .iter()
+                .map(|portion| (portion.user_id, portion.amount, portion.value))
+                .collect::<Vec<_>>(),
+            vec![(members[0], 500, 1), (members[1], 1500, 3)]
+        );
+    }
+
+    #[test]
+    fn exact_splits_cannot_be_rescaled() {
+        let members = [Uuid::new_v4(), Uuid::new_v4()];
+        let existing = portions(&members, &[250, 750]);
+
+        let error = rescale_portions(2000, SplitMethod::Exact, &existing, &members)
+            .err()
+            .unwrap();
+
+        assert_eq!(field_error_code(error), "required");
+    }
+
+    #[test]
+    fn splits_with_departed_members_cannot_be_rescaled() {
+        let members = [Uuid::new_v4(), Uuid::new_v4()];
+        let existing = portions(&members, &[1, 1]);
+
+        let error = rescale_portions(2000, SplitMethod::Equal, &existing, &members[..1])
+            .err()
+            .unwrap();
+
+        assert_eq!(field_error_code(error), "member_left");
+    }
+}
@@ -0,0 +1,206 @@
+use super::split::Portion;
+use crate::api::error::ErrorType::{BillNotFound, Forbidden};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::db::bill::{Bill, BillChangeset, BillItem, BillSplit, NewBill};
+use crate::db::database::Connection;
+use crate::db::household::HouseholdMember;
+use crate::db::schema::{bill_items, bill_splits, bills};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+/// Creates a bill along with the portion each member owes.
+pub async fn create_bill(
+    conn: &mut Connection,
+    bill: NewBill,
+    portions: Vec<Portion>,
+) -> Result<Bill, APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let bill = diesel::insert_into(bills::table)
+                .values(&bill)
+                .returning(Bill::as_returning())
+                .get_result(conn)
+                .await?;
+
+            insert_splits(conn, bill.id, portions).await?;
+
+            Ok(bill)
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+pub(crate) async fn insert_splits(
+    conn: &mut Connection,
+    bill_id: Uuid,
+    portions: Vec<Portion>,
+) -> Result<Vec<BillSplit>, diesel::result::Error> {
+    let splits: Vec<BillSplit> = portions
+        .into_iter()
+        .map(|portion| BillSplit {
+            bill_id,
+            user_id: portion.user_id,
+            amount: portion.amount,
+            value: portion.value,
+        })
+        .collect();
+
+    diesel::insert_into(bill_splits::table)
+        .values(&splits)
+        .returning(BillSplit::as_returning())
+        .get_results(conn)
+        .await
+}
+
+pub async fn find_bill(
+    conn: &mut Connection,
+    household_id: Uuid,
+    bill_id: Uuid,
+) -> Result<Bill, APIError> {
+    Bill::all()
+        .filter(bills::id.eq(bill_id))
+        .filter(bills::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(BillNotFound).build())
+}
+
+pub async fn find_bills_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Bill>, APIError> {
+    Bill::all()
+        .filter(bills::household_id.eq(household_id))
+        .order(bills::created_at.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the splits of the given bills, grouped by bill in the same order.
+pub async fn find_splits_for_bills(
+    conn: &mut Connection,
+    bills: &[Bill],
+) -> Result<Vec<Vec<BillSplit>>, APIError> {
+    let splits = BillSplit::belonging_to(bills)
+        .select(BillSplit::as_select())
+        .order(bill_splits::amount.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(splits.grouped_by(bills))
+}
+
+/// Finds the line items of the given bills, grouped by bill in the same order.
+pub async fn find_items_for_bills(
+    conn: &mut Connection,
+    bills: &[Bill],
+) -> Result<Vec<Vec<BillItem>>, APIError> {
+    let items = BillItem::belonging_to(bills)
+        .select(BillItem::as_select())
+        .order((bill_items::created_at, bill_items::name))
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(items.grouped_by(bills))
+}
+
+/// A bill along with its splits and line items.
+pub type BillDetails = (Bill, Vec<BillSplit>, Vec<BillItem>);
+
+/// Loads the splits and line items of the given bills.
+pub async fn load_details(
+    conn: &mut Connection,
+    bills: Vec<Bill>,
+) -> Result<Vec<BillDetails>, APIError> {
+    let splits = find_splits_for_bills(conn, &bills).await?;
+    let items = find_items_for_bills(conn, &bills).await?;
+
+    Ok(bills
+        .into_iter()
+        .zip(splits)
+        .zip(items)
+        .map(|((bill, splits), items)| (bill, splits, items))
+        .collect())
+}
+
+/// Loads the splits and line items of a bill.
+pub async fn load_bill_details(conn: &mut Connection, bill: Bill) -> Result<BillDetails, APIError> {
+    let mut details = load_details(conn, vec![bill]).await?;
+    Ok(details.remove(0))
+}
+
+/// Updates a bill, replacing its splits if new portions are given.
+pub async fn update_bill(
+    conn: &mut Connection,
+    bill_id: Uuid,
+    changes: BillChangeset,
+    portions: Option<Vec<Portion>>,
+) -> Result<Bill, APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let bill = diesel::update(bills::table.find(bill_id))
+                .set(&changes)
+                .returning(Bill::as_returning())
+                .get_result(conn)
+                .await?;
+
+            if let Some(portions) = portions {
+                diesel::delete(bill_splits::table.filter(bill_splits::bill_id.eq(bill_id)))
+                    .execute(conn)
+                    .await?;
+                insert_splits(conn, bill_id, portions).await?;
+            }
+
+            Ok(bill)
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+pub async fn delete_bill(conn: &mut Connection, bill_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(bills::table.find(bill_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Ensures the current user can change a bill. Owners, admins, the payer and the creator of a bill can.
+pub fn require_bill_editor(
+    bill: &Bill,
+    membership: &HouseholdMember,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    if membership.role.can_manage() || bill.payer_id == user_id || bill.created_by == Some(user_id)
+    {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(Forbidden)
+        .detail("Only owners, admins, the payer and the creator of a bill can change it.")
+        .build())
+}
+
+/// Turns the saved splits of a bill back into portions, so they can be applied to a new amount.
+pub fn existing_portions(splits: &[BillSplit]) -> Vec<Portion> {
+    splits
+        .iter()
+        .map(|split| Portion {
+            user_id: split.user_id,
+            amount: split.amount,
+            value: split.value,
+        })
+        .collect()
+}
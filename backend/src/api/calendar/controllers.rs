@@ -0,0 +1,507 @@
+use super::ics::CalendarWriter;
+use super::models::{
+    CalendarFeedQuery, CalendarFeedResponse, CreateEventRequest, EventResponse, RsvpRequest,
+    UpdateEventRequest,
+};
+use super::utils::{
+    create_event as insert_event, create_feed_token_if_missing, delete_event as remove_event,
+    delete_rsvp, feed_url, find_bills_with_due_date, find_event, find_event_with_rsvps,
+    find_events_for_household, find_feed_token_user, find_pending_occurrences,
+    find_rsvps_for_events, require_event_editor, reset_feed_token, save_rsvp,
+    update_event as save_event, validate_schedule,
+};
+use crate::api::error::ErrorType::Unauthorized;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::find_household_for_user;
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::{Path, Query};
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::ValidatedJson;
+use crate::db::event::{EventChangeset, EventRsvp, NewEvent};
+use crate::AppState;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::{Extension, Json};
+use tracing::info;
+
+/// Create an event
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/calendar/events",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateEventRequest
+    ),
+    responses(
+        (status = 201, description = "Created event successfully", body = EventResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_event(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateEventRequest>,
+) -> Result<(StatusCode, Json<EventResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    validate_schedule(
+        payload.starts_at,
+        payload.ends_at,
+        payload.all_day,
+        payload.recurrence.as_ref().and_then(|r| r.until),
+    )?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    info!(user_id = %current_user.id, household_id = %household_id, "creating event");
+    let recurrence = payload.recurrence;
+    let event = insert_event(
+        &mut conn,
+        NewEvent {
+            household_id,
+            created_by: Some(current_user.id),
+            title: payload.title,
+            description: payload.description,
+            location: payload.location,
+            starts_at: payload.starts_at,
+            ends_at: payload.ends_at,
+            timezone: payload.timezone,
+            all_day: payload.all_day,
+            recurrence_frequency: recurrence.as_ref().map(|r| r.frequency),
+            recurrence_interval: recurrence.as_ref().map_or(1, |r| r.interval),
+            recurrence_until: recurrence.and_then(|r| r.until),
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json((event, Vec::new()).into())))
+}
+
+/// List the events of a household
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/calendar/events",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [EventResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_events(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<EventResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let events = find_events_for_household(&mut conn, household_id).await?;
+    let rsvps = find_rsvps_for_events(&mut conn, &events).await?;
+
+    let response = events
+        .into_iter()
+        .zip(rsvps)
+        .map(EventResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get an event
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/calendar/events/{event_id}",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("event_id" = String, Path, description = "The event id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = EventResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or event not found", body = APIError),
+    )
+)]
+pub async fn get_event(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, event_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<EventResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let event_id = parse_friendly_id(&event_id, ItemIdType::Event)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let event = find_event_with_rsvps(&mut conn, household_id, event_id).await?;
+    Ok((StatusCode::OK, Json(event.into())))
+}
+
+/// Update an event
+///
+/// Only owners, admins and the creator of the event can update it.
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/calendar/events/{event_id}",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("event_id" = String, Path, description = "The event id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateEventRequest
+    ),
+    responses(
+        (status = 200, description = "Updated event successfully", body = EventResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to update the event", body = APIError),
+        (status = 404, description = "Household or event not found", body = APIError),
+    )
+)]
+pub async fn update_event(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, event_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateEventRequest>,
+) -> Result<(StatusCode, Json<EventResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let event_id = parse_friendly_id(&event_id, ItemIdType::Event)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let event = find_event(&mut conn, household_id, event_id).await?;
+    require_event_editor(&event, &membership, current_user.id)?;
+
+    validate_schedule(
+        payload.starts_at.unwrap_or(event.starts_at),
+        payload.ends_at.unwrap_or(event.ends_at),
+        payload.all_day.unwrap_or(event.all_day),
+        match &payload.recurrence {
+            Some(recurrence) => recurrence.until,
+            None => event.recurrence_until,
+        },
+    )?;
+
+    let recurrence = payload.recurrence;
+    let changes = EventChangeset {
+        title: payload.title,
+        description: payload.description.map(Some),
+        location: payload.location.map(Some),
+        starts_at: payload.starts_at,
+        ends_at: payload.ends_at,
+        timezone: payload.timezone,
+        all_day: payload.all_day,
+        recurrence_frequency: recurrence.as_ref().map(|r| Some(r.frequency)),
+        recurrence_interval: recurrence.as_ref().map(|r| r.interval),
+        recurrence_until: recurrence.map(|r| r.until),
+    };
+    if has_changes(&changes) {
+        info!(user_id = %current_user.id, event_id = %event_id, "updating event");
+        save_event(&mut conn, event_id, changes).await?;
+    }
+
+    let event = find_event_with_rsvps(&mut conn, household_id, event_id).await?;
+    Ok((StatusCode::OK, Json(event.into())))
+}
+
+/// Delete an event
+///
+/// Only owners, admins and the creator of the event can delete it.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/calendar/events/{event_id}",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("event_id" = String, Path, description = "The event id"),
+    ),
+    responses(
+        (status = 204, description = "Deleted event successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to delete the event", body = APIError),
+        (status = 404, description = "Household or event not found", body = APIError),
+    )
+)]
+pub async fn delete_event(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, event_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let event_id = parse_friendly_id(&event_id, ItemIdType::Event)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let event = find_event(&mut conn, household_id, event_id).await?;
+    require_event_editor(&event, &membership, current_user.id)?;
+
+    info!(user_id = %current_user.id, event_id = %event_id, "deleting event");
+    remove_event(&mut conn, event_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Reply to an event
+///
+/// Replaces any earlier reply of the current user.
+#[utoipa::path(
+    put,
+    path = "/households/{household_id}/calendar/events/{event_id}/rsvp",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("event_id" = String, Path, description = "The event id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = RsvpRequest
+    ),
+    responses(
+        (status = 200, description = "Saved reply successfully", body = EventResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or event not found", body = APIError),
+    )
+)]
+pub async fn rsvp_to_event(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, event_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<RsvpRequest>,
+) -> Result<(StatusCode, Json<EventResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let event_id = parse_friendly_id(&event_id, ItemIdType::Event)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_event(&mut conn, household_id, event_id).await?;
+
+    save_rsvp(
+        &mut conn,
+        EventRsvp {
+            event_id,
+            user_id: current_user.id,
+            status: payload.status,
+            responded_at: chrono::Utc::now().naive_utc(),
+        },
+    )
+    .await?;
+
+    let event = find_event_with_rsvps(&mut conn, household_id, event_id).await?;
+    Ok((StatusCode::OK, Json(event.into())))
+}
+
+/// Withdraw a reply to an event
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/calendar/events/{event_id}/rsvp",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("event_id" = String, Path, description = "The event id"),
+    ),
+    responses(
+        (status = 200, description = "Withdrew reply successfully", body = EventResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or event not found", body = APIError),
+    )
+)]
+pub async fn withdraw_rsvp(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, event_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<EventResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let event_id = parse_friendly_id(&event_id, ItemIdType::Event)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_event(&mut conn, household_id, event_id).await?;
+
+    delete_rsvp(&mut conn, event_id, current_user.id).await?;
+
+    let event = find_event_with_rsvps(&mut conn, household_id, event_id).await?;
+    Ok((StatusCode::OK, Json(event.into())))
+}
+
+/// Get the calendar feed url of the current user
+///
+/// The feed includes the household's events, chores that are due and bill due dates. Feed tokens are only stored
+/// hashed, so the token and url are only returned the first time; reset the token to get a new url.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/calendar/feed",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = CalendarFeedResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn get_calendar_feed(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<CalendarFeedResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let token = create_feed_token_if_missing(&mut conn, current_user.id).await?;
+    let url = token
+        .as_ref()
+        .map(|token| feed_url(&state.settings.server.public_url, household_id, token));
+
+    Ok((StatusCode::OK, Json(CalendarFeedResponse { token, url })))
+}
+
+/// Reset the calendar feed token of the current user
+///
+/// Feed urls with the old token stop working, in every household.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/calendar/feed/reset",
+    tag = "calendar",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Reset feed token successfully", body = CalendarFeedResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn reset_calendar_feed(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<CalendarFeedResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    info!(user_id = %current_user.id, "resetting calendar feed token");
+    let token = reset_feed_token(&mut conn, current_user.id).await?;
+    let url = feed_url(&state.settings.server.public_url, household_id, &token);
+
+    Ok((
+        StatusCode::OK,
+        Json(CalendarFeedResponse {
+            token: Some(token),
+            url: Some(url),
+        }),
+    ))
+}
+
+/// Download the calendar feed of a household
+///
+/// Authenticated by the feed token in the url instead of a bearer token, so calendar apps can subscribe to it.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/calendar/feed.ics",
+    tag = "calendar",
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        CalendarFeedQuery,
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "text/calendar", body = String),
+        (status = 401, description = "Feed token is invalid", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn download_calendar_feed(
+    State(state): State<AppState>,
+    Path(household_id): Path<String>,
+    Query(query): Query<CalendarFeedQuery>,
+) -> Result<(StatusCode, [(header::HeaderName, &'static str); 1], String), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let user_id = find_feed_token_user(&mut conn, &query.token)
+        .await?
+        .ok_or_else(|| APIErrorBuilder::new(Unauthorized).build())?;
+    let (household, _) = find_household_for_user(&mut conn, household_id, user_id).await?;
+
+    let events = find_events_for_household(&mut conn, household_id).await?;
+    let occurrences = find_pending_occurrences(&mut conn, household_id).await?;
+    let bills = find_bills_with_due_date(&mut conn, household_id).await?;
+
+    let mut calendar = CalendarWriter::new(&household.name);
+    for event in &events {
+        calendar.event(event);
+    }
+    for (occurrence, chore_name) in &occurrences {
+        calendar.chore(occurrence, chore_name);
+    }
+    for bill in &bills {
+        calendar.bill(bill);
+    }
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar.finish(),
+    ))
+}
+
+fn has_changes(changes: &EventChangeset) -> bool {
+    changes.title.is_some()
+        || changes.description.is_some()
+        || changes.location.is_some()
+        || changes.starts_at.is_some()
+        || changes.ends_at.is_some()
+        || changes.timezone.is_some()
+        || changes.all_day.is_some()
+        || changes.recurrence_frequency.is_some()
+        || changes.recurrence_interval.is_some()
+        || changes.recurrence_until.is_some()
+}
@@ -0,0 +1,194 @@
+//! A minimal writer for iCalendar (RFC 5545) feeds.
+//!
+//! Timed events reference their timezone by its IANA name through `TZID` without a `VTIMEZONE`
+//! definition, which the common calendar apps resolve themselves.
+
+use crate::db::bill::Bill;
+use crate::db::chore::ChoreOccurrence;
+use crate::db::event::{Event, EventFrequency};
+use chrono::{Duration, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use std::fmt::Write;
+
+/// The longest a content line can be, in octets, before it has to be folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+const DATE_FORMAT: &str = "%Y%m%d";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const UTC_DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+pub struct CalendarWriter {
+    output: String,
+}
+
+impl CalendarWriter {
+    pub fn new(name: &str) -> Self {
+        let mut writer = Self {
+            output: String::new(),
+        };
+
+        writer.line("BEGIN:VCALENDAR");
+        writer.line("VERSION:2.0");
+        writer.line("PRODID:-//Domus//Household Calendar//EN");
+        writer.line("CALSCALE:GREGORIAN");
+        writer.line("METHOD:PUBLISH");
+        writer.line(&format!("X-WR-CALNAME:{}", escape_text(name)));
+        writer
+    }
+
+    pub fn event(&mut self, event: &Event) {
+        self.line("BEGIN:VEVENT");
+        self.line(&format!("UID:{}@domus", event.id));
+        self.line(&format!(
+            "DTSTAMP:{}",
+            event
+                .updated_at
+                .unwrap_or(event.created_at)
+                .format(UTC_DATE_TIME_FORMAT)
+        ));
+
+        if event.all_day {
+            // the end date of an all-day event is exclusive
+            self.line(&format!(
+                "DTSTART;VALUE=DATE:{}",
+                event.starts_at.format(DATE_FORMAT)
+            ));
+            self.line(&format!(
+                "DTEND;VALUE=DATE:{}",
+                (event.ends_at.date() + Duration::days(1)).format(DATE_FORMAT)
+            ));
+        } else {
+            self.line(&format!(
+                "DTSTART;TZID={}:{}",
+                event.timezone,
+                event.starts_at.format(DATE_TIME_FORMAT)
+            ));
+            self.line(&format!(
+                "DTEND;TZID={}:{}",
+                event.timezone,
+                event.ends_at.format(DATE_TIME_FORMAT)
+            ));
+        }
+
+        if let Some(frequency) = event.recurrence_frequency {
+            let mut rule = format!(
+                "RRULE:FREQ={};INTERVAL={}",
+                frequency_name(frequency),
+                event.recurrence_interval
+            );
+            if let Some(until) = event.recurrence_until {
+                let _ = write!(rule, ";UNTIL={}", recurrence_until(event, until));
+            }
+            self.line(&rule);
+        }
+
+        self.line(&format!("SUMMARY:{}", escape_text(&event.title)));
+        if let Some(description) = &event.description {
+            self.line(&format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(location) = &event.location {
+            self.line(&format!("LOCATION:{}", escape_text(location)));
+        }
+        self.line("END:VEVENT");
+    }
+
+    /// Adds a chore that is due as an all-day event.
+    pub fn chore(&mut self, occurrence: &ChoreOccurrence, name: &str) {
+        self.all_day(
+            &occurrence.id.to_string(),
+            occurrence.created_at,
+            occurrence.due_date,
+            name,
+        );
+    }
+
+    /// Adds the due date of a bill as an all-day event.
+    pub fn bill(&mut self, bill: &Bill) {
+        if let Some(due_date) = bill.due_date {
+            self.all_day(
+                &bill.id.to_string(),
+                bill.created_at,
+                due_date,
+                &format!("{} due", bill.description),
+            );
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        self.line("END:VCALENDAR");
+        self.output
+    }
+
+    fn all_day(&mut self, id: &str, stamp: NaiveDateTime, date: NaiveDate, summary: &str) {
+        self.line("BEGIN:VEVENT");
+        self.line(&format!("UID:{}@domus", id));
+        self.line(&format!("DTSTAMP:{}", stamp.format(UTC_DATE_TIME_FORMAT)));
+        self.line(&format!("DTSTART;VALUE=DATE:{}", date.format(DATE_FORMAT)));
+        self.line(&format!(
+            "DTEND;VALUE=DATE:{}",
+            (date + Duration::days(1)).format(DATE_FORMAT)
+        ));
+        self.line(&format!("SUMMARY:{}", escape_text(summary)));
+        self.line("TRANSP:TRANSPARENT");
+        self.line("END:VEVENT");
+    }
+
+    /// Writes a content line, folding it onto continuation lines if it is too long.
+    fn line(&mut self, content: &str) {
+        let mut octets = 0;
+        for c in content.chars() {
+            if octets + c.len_utf8() > MAX_LINE_OCTETS {
+                self.output.push_str("\r\n ");
+                // the leading space of a continuation line counts towards its length
+                octets = 1;
+            }
+            self.output.push(c);
+            octets += c.len_utf8();
+        }
+        self.output.push_str("\r\n");
+    }
+}
+
+fn frequency_name(frequency: EventFrequency) -> &'static str {
+    match frequency {
+        EventFrequency::Daily => "DAILY",
+        EventFrequency::Weekly => "WEEKLY",
+        EventFrequency::Monthly => "MONTHLY",
+        EventFrequency::Yearly => "YEARLY",
+    }
+}
+
+/// Formats the last day an event can repeat on as an `UNTIL` value.
+///
+/// All-day events use a date, while timed events must use the end of that day in UTC.
+fn recurrence_until(event: &Event, until: NaiveDate) -> String {
+    if event.all_day {
+        return until.format(DATE_FORMAT).to_string();
+    }
+
+    let end_of_day = until.and_hms_opt(23, 59, 59).unwrap_or_default();
+    let utc = event
+        .timezone
+        .parse::<Tz>()
+        .ok()
+        .and_then(|tz| tz.from_local_datetime(&end_of_day).latest())
+        .map(|local| local.naive_utc())
+        .unwrap_or(end_of_day);
+
+    utc.format(UTC_DATE_TIME_FORMAT).to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,31 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{get, post, put};
+use axum::{middleware, Router};
+use controllers::{
+    create_event, delete_event, download_calendar_feed, get_calendar_feed, get_event, list_events,
+    reset_calendar_feed, rsvp_to_event, update_event, withdraw_rsvp,
+};
+
+pub mod controllers;
+mod ics;
+pub mod models;
+mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/events", post(create_event).get(list_events))
+        .route(
+            "/events/:event_id",
+            get(get_event).patch(update_event).delete(delete_event),
+        )
+        .route(
+            "/events/:event_id/rsvp",
+            put(rsvp_to_event).delete(withdraw_rsvp),
+        )
+        .route("/feed", get(get_calendar_feed))
+        .route("/feed/reset", post(reset_calendar_feed))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+        // calendar apps authenticate with the feed token instead
+        .route("/feed.ics", get(download_calendar_feed))
+}
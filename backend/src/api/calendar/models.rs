@@ -0,0 +1,196 @@
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::event::{Event, EventFrequency, EventRsvp, RsvpStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationError};
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateEventRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "House meeting", min_length = 1, max_length = 128)]
+    pub title: String,
+
+    #[validate(length(max = 2000))]
+    #[schema(example = "Agenda: the new cleaning roster.", max_length = 2000)]
+    pub description: Option<String>,
+
+    #[validate(length(min = 1, max = 256))]
+    #[schema(example = "Living room", min_length = 1, max_length = 256)]
+    pub location: Option<String>,
+
+    /// The local time the event starts at, in the event's timezone.
+    ///
+    /// Only the date is used for all-day events.
+    #[schema(example = "2026-10-20T19:00:00")]
+    pub starts_at: chrono::NaiveDateTime,
+
+    /// The local time the event ends at, in the event's timezone. Must not be before the start.
+    ///
+    /// Only the date is used for all-day events, and the event runs to the end of that day.
+    #[schema(example = "2026-10-20T20:00:00")]
+    pub ends_at: chrono::NaiveDateTime,
+
+    /// An IANA timezone name.
+    #[validate(custom = "validate_timezone")]
+    #[schema(example = "Pacific/Auckland")]
+    pub timezone: String,
+
+    #[serde(default)]
+    pub all_day: bool,
+
+    #[validate]
+    pub recurrence: Option<EventRecurrence>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateEventRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "House meeting", min_length = 1, max_length = 128)]
+    pub title: Option<String>,
+
+    #[validate(length(max = 2000))]
+    #[schema(example = "Agenda: the new cleaning roster.", max_length = 2000)]
+    pub description: Option<String>,
+
+    #[validate(length(min = 1, max = 256))]
+    #[schema(example = "Living room", min_length = 1, max_length = 256)]
+    pub location: Option<String>,
+
+    #[schema(example = "2026-10-20T19:00:00")]
+    pub starts_at: Option<chrono::NaiveDateTime>,
+
+    #[schema(example = "2026-10-20T20:00:00")]
+    pub ends_at: Option<chrono::NaiveDateTime>,
+
+    /// An IANA timezone name.
+    #[validate(custom = "validate_timezone")]
+    #[schema(example = "Pacific/Auckland")]
+    pub timezone: Option<String>,
+
+    pub all_day: Option<bool>,
+
+    #[validate]
+    pub recurrence: Option<EventRecurrence>,
+}
+
+#[derive(Serialize, Deserialize, Validate, ToSchema, Debug)]
+pub struct EventRecurrence {
+    pub frequency: EventFrequency,
+
+    /// Repeat every `interval` days, weeks, months or years.
+    #[serde(default = "default_interval")]
+    #[validate(range(min = 1, max = 99))]
+    #[schema(example = 1, minimum = 1, maximum = 99, default = 1)]
+    pub interval: i32,
+
+    /// The last day the event can repeat on. Repeats forever if not given.
+    pub until: Option<chrono::NaiveDate>,
+}
+
+fn default_interval() -> i32 {
+    1
+}
+
+pub fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    match timezone.parse::<chrono_tz::Tz>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(ValidationError::new("timezone")),
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EventResponse {
+    #[schema(example = "event|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    #[schema(example = "House meeting")]
+    pub title: String,
+    pub description: Option<String>,
+    #[schema(example = "Living room")]
+    pub location: Option<String>,
+    #[schema(example = "2026-10-20T19:00:00")]
+    pub starts_at: chrono::NaiveDateTime,
+    #[schema(example = "2026-10-20T20:00:00")]
+    pub ends_at: chrono::NaiveDateTime,
+    #[schema(example = "Pacific/Auckland")]
+    pub timezone: String,
+    pub all_day: bool,
+    pub recurrence: Option<EventRecurrence>,
+    pub rsvps: Vec<RsvpResponse>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<(Event, Vec<EventRsvp>)> for EventResponse {
+    fn from((event, rsvps): (Event, Vec<EventRsvp>)) -> Self {
+        let recurrence = event.recurrence_frequency.map(|frequency| EventRecurrence {
+            frequency,
+            interval: event.recurrence_interval,
+            until: event.recurrence_until,
+        });
+
+        Self {
+            id: event.id.to_friendly_id(ItemIdType::Event),
+            household_id: event.household_id.to_friendly_id(ItemIdType::Household),
+            title: event.title,
+            description: event.description,
+            location: event.location,
+            starts_at: event.starts_at,
+            ends_at: event.ends_at,
+            timezone: event.timezone,
+            all_day: event.all_day,
+            recurrence,
+            rsvps: rsvps.into_iter().map(RsvpResponse::from).collect(),
+            created_by: event
+                .created_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            created_at: event.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RsvpResponse {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub member_id: String,
+    pub status: RsvpStatus,
+    pub responded_at: chrono::NaiveDateTime,
+}
+
+impl From<EventRsvp> for RsvpResponse {
+    fn from(rsvp: EventRsvp) -> Self {
+        Self {
+            member_id: rsvp.user_id.to_friendly_id(ItemIdType::User),
+            status: rsvp.status,
+            responded_at: rsvp.responded_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct RsvpRequest {
+    pub status: RsvpStatus,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CalendarFeedResponse {
+    /// The secret that authenticates the feed. Anyone with it can read the household calendar.
+    ///
+    /// Only returned when the token is created or reset, since it isn't stored.
+    #[schema(example = "4XbFzGDGpKfKMSzrV8MQtH")]
+    pub token: Option<String>,
+    /// The url to subscribe to from a calendar app. Only returned along with the token.
+    #[schema(
+        example = "http://127.0.0.1:3000/v1/households/household|4XbFzGDGpKfKMSzrV8MQtH/calendar/feed.ics?token=4XbFzGDGpKfKMSzrV8MQtH"
+    )]
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct CalendarFeedQuery {
+    /// The calendar feed token of a household member.
+    pub token: String,
+}
@@ -0,0 +1,297 @@
+use crate::api::error::ErrorType::{EventNotFound, Forbidden};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::api::utils::secret::hash_secret;
+use crate::api::utils::validation::field_error;
+use crate::db::bill::Bill;
+use crate::db::calendar_feed_token::NewCalendarFeedToken;
+use crate::db::chore::{ChoreOccurrence, OccurrenceStatus};
+use crate::db::database::Connection;
+use crate::db::event::{Event, EventChangeset, EventRsvp, NewEvent};
+use crate::db::household::HouseholdMember;
+use crate::db::schema::{
+    bills, calendar_feed_tokens, chore_occurrences, chores, event_rsvps, events,
+};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn create_event(conn: &mut Connection, event: NewEvent) -> Result<Event, APIError> {
+    diesel::insert_into(events::table)
+        .values(&event)
+        .returning(Event::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn find_event(
+    conn: &mut Connection,
+    household_id: Uuid,
+    event_id: Uuid,
+) -> Result<Event, APIError> {
+    Event::all()
+        .filter(events::id.eq(event_id))
+        .filter(events::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(EventNotFound).build())
+}
+
+pub async fn find_events_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Event>, APIError> {
+    Event::all()
+        .filter(events::household_id.eq(household_id))
+        .order(events::starts_at)
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the RSVPs to the given events, grouped by event in the same order.
+pub async fn find_rsvps_for_events(
+    conn: &mut Connection,
+    events: &[Event],
+) -> Result<Vec<Vec<EventRsvp>>, APIError> {
+    let rsvps = EventRsvp::belonging_to(events)
+        .select(EventRsvp::as_select())
+        .order(event_rsvps::responded_at)
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(rsvps.grouped_by(events))
+}
+
+/// Finds an event along with its RSVPs.
+pub async fn find_event_with_rsvps(
+    conn: &mut Connection,
+    household_id: Uuid,
+    event_id: Uuid,
+) -> Result<(Event, Vec<EventRsvp>), APIError> {
+    let event = find_event(conn, household_id, event_id).await?;
+    let rsvps = find_rsvps_for_events(conn, std::slice::from_ref(&event))
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    Ok((event, rsvps))
+}
+
+pub async fn update_event(
+    conn: &mut Connection,
+    event_id: Uuid,
+    changes: EventChangeset,
+) -> Result<(), APIError> {
+    diesel::update(events::table.find(event_id))
+        .set(&changes)
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+pub async fn delete_event(conn: &mut Connection, event_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(events::table.find(event_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Records a member's reply to an event, replacing any earlier reply.
+pub async fn save_rsvp(conn: &mut Connection, rsvp: EventRsvp) -> Result<(), APIError> {
+    diesel::insert_into(event_rsvps::table)
+        .values(&rsvp)
+        .on_conflict((event_rsvps::event_id, event_rsvps::user_id))
+        .do_update()
+        .set((
+            event_rsvps::status.eq(excluded(event_rsvps::status)),
+            event_rsvps::responded_at.eq(excluded(event_rsvps::responded_at)),
+        ))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+pub async fn delete_rsvp(
+    conn: &mut Connection,
+    event_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    diesel::delete(event_rsvps::table.find((event_id, user_id)))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Ensures the current user can change an event. Owners, admins and the creator of an event can.
+pub fn require_event_editor(
+    event: &Event,
+    membership: &HouseholdMember,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    if membership.role.can_manage() || event.created_by == Some(user_id) {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(Forbidden)
+        .detail("Only owners, admins and the creator of an event can change it.")
+        .build())
+}
+
+pub fn generate_feed_token() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+
+    base62::encode(u128::from_le_bytes(bytes))
+}
+
+/// Creates a calendar feed token for a user if they don't have one yet.
+///
+/// Only a hash of the token is kept, so it is returned only when it was created.
+pub async fn create_feed_token_if_missing(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<Option<String>, APIError> {
+    let token = generate_feed_token();
+    let created = diesel::insert_into(calendar_feed_tokens::table)
+        .values(&NewCalendarFeedToken {
+            user_id,
+            token_hash: hash_secret(&token),
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok((created > 0).then_some(token))
+}
+
+/// Replaces the calendar feed token of a user, so that urls with the old token stop working.
+///
+/// Returns the new token, which can't be retrieved again afterwards.
+pub async fn reset_feed_token(conn: &mut Connection, user_id: Uuid) -> Result<String, APIError> {
+    let token = generate_feed_token();
+    diesel::insert_into(calendar_feed_tokens::table)
+        .values(&NewCalendarFeedToken {
+            user_id,
+            token_hash: hash_secret(&token),
+        })
+        .on_conflict(calendar_feed_tokens::user_id)
+        .do_update()
+        .set((
+            calendar_feed_tokens::token_hash.eq(excluded(calendar_feed_tokens::token_hash)),
+            calendar_feed_tokens::created_at.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(token)
+}
+
+/// Finds the user a calendar feed token belongs to.
+pub async fn find_feed_token_user(
+    conn: &mut Connection,
+    token: &str,
+) -> Result<Option<Uuid>, APIError> {
+    calendar_feed_tokens::table
+        .filter(calendar_feed_tokens::token_hash.eq(hash_secret(token)))
+        .select(calendar_feed_tokens::user_id)
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)
+}
+
+/// Finds the chore occurrences of a household that are still to be done, along with the name of their chore.
+pub async fn find_pending_occurrences(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<(ChoreOccurrence, String)>, APIError> {
+    chore_occurrences::table
+        .inner_join(chores::table)
+        .filter(chores::household_id.eq(household_id))
+        .filter(chore_occurrences::status.eq(OccurrenceStatus::Pending))
+        .select((ChoreOccurrence::as_select(), chores::name))
+        .order(chore_occurrences::due_date)
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the bills of a household that have a due date.
+pub async fn find_bills_with_due_date(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Bill>, APIError> {
+    Bill::all()
+        .filter(bills::household_id.eq(household_id))
+        .filter(bills::due_date.is_not_null())
+        .order(bills::due_date)
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Ensures an event doesn't end before it starts, or stop repeating before it starts.
+///
+/// All-day events are compared by date, since their times are ignored.
+pub fn validate_schedule(
+    starts_at: NaiveDateTime,
+    ends_at: NaiveDateTime,
+    all_day: bool,
+    recurrence_until: Option<NaiveDate>,
+) -> Result<(), APIError> {
+    let ends_before_start = if all_day {
+        ends_at.date() < starts_at.date()
+    } else {
+        ends_at < starts_at
+    };
+    if ends_before_start {
+        return Err(field_error(
+            "ends_at",
+            "range",
+            "The event must not end before it starts.",
+        ));
+    }
+
+    if recurrence_until.is_some_and(|until| until < starts_at.date()) {
+        return Err(field_error(
+            "recurrence",
+            "range",
+            "The event must not stop repeating before it starts.",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Builds the url calendar apps can subscribe to for a household feed.
+pub fn feed_url(public_url: &str, household_id: Uuid, token: &str) -> String {
+    // the separator in friendly ids has to be escaped in urls
+    let household_id = household_id
+        .to_friendly_id(ItemIdType::Household)
+        .replace('|', "%7C");
+
+    format!(
+        "{}/v1/households/{}/calendar/feed.ics?token={}",
+        public_url.trim_end_matches('/'),
+        household_id,
+        token
+    )
+}
@@ -0,0 +1,275 @@
+use super::models::{
+    ChatHistoryQuery, ChatHistoryResponse, ChatMessageResponse, ChatSocketQuery,
+    SendChatMessageRequest,
+};
+use super::utils::{create_message, find_messages};
+use crate::api::error::ErrorType::ValidationError;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::find_household_for_user;
+use crate::api::middleware::{
+    authenticate_socket, check_not_revoked, exposing_causes, CurrentUser,
+};
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::{Path, Query, WebSocketUpgrade};
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType, ToFriendlyId};
+use crate::db::chat_message::NewChatMessage;
+use crate::AppState;
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use axum::{Extension, Json};
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{interval_at, sleep, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+/// How often a connection checks that its member is still in the household and signed in.
+const REAUTHORIZE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Connect to the household chat
+///
+/// Upgrades to a WebSocket. Clients send `SendChatMessageRequest`s as text frames, and receive every
+/// `ChatMessageResponse` sent to the household, including their own, or an `APIError` if their message was rejected.
+///
+/// The socket is closed when the token it was opened with expires or is revoked, or when the member leaves the
+/// household.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/chat/ws",
+    tag = "chat",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ChatSocketQuery,
+    ),
+    responses(
+        (status = 101, description = "Switched to the WebSocket protocol"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn chat_socket(
+    State(state): State<AppState>,
+    Path(household_id): Path<String>,
+    Query(query): Query<ChatSocketQuery>,
+    headers: HeaderMap,
+    WebSocketUpgrade(ws): WebSocketUpgrade,
+) -> Result<Response, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let current_user = authenticate_socket(&state, &headers, query.token.as_deref()).await?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    drop(conn);
+
+    // the socket outlives the request, so errors sent over it need the error settings of their own
+    let expose_causes = state.settings.errors.expose_causes;
+    Ok(ws.on_upgrade(move |socket| {
+        exposing_causes(
+            expose_causes,
+            handle_socket(socket, state, household_id, current_user),
+        )
+    }))
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    state: AppState,
+    household_id: Uuid,
+    current_user: CurrentUser,
+) {
+    let user_id = current_user.id;
+    let mut messages = state.chat.subscribe(household_id);
+    info!(user_id = %user_id, household_id = %household_id, "member connected to chat");
+
+    let expires_in = (current_user.expires_at - chrono::Utc::now().naive_utc())
+        .to_std()
+        .unwrap_or_default();
+    let expired = sleep(expires_in);
+    tokio::pin!(expired);
+    let mut reauthorize = interval_at(Instant::now() + REAUTHORIZE_INTERVAL, REAUTHORIZE_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = &mut expired => {
+                info!(user_id = %user_id, "chat token expired");
+                close(&mut socket, "The token you provided has expired.").await;
+                break;
+            }
+            _ = reauthorize.tick() => {
+                if let Err(error) = authorize(&state, household_id, &current_user).await {
+                    info!(user_id = %user_id, household_id = %household_id, "member is no longer allowed in chat");
+                    error.log();
+                    let _ = send_json(&mut socket, &error).await;
+                    close(&mut socket, "You are no longer allowed in this chat.").await;
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Err(error) = receive_message(&state, household_id, &current_user, &text).await {
+                        error.log();
+                        if send_json(&mut socket, &error).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // pings are answered by axum, and binary frames aren't part of the protocol
+                Some(Ok(_)) => {}
+            },
+            outgoing = messages.recv() => match outgoing {
+                Ok(message) => {
+                    // revocations are cached, so this is cheap enough to do for every message
+                    if let Err(error) = check_not_revoked(&state, &current_user).await {
+                        error.log();
+                        let _ = send_json(&mut socket, &error).await;
+                        close(&mut socket, "The token you provided has been revoked.").await;
+                        break;
+                    }
+                    if send_json(&mut socket, &message).await.is_err() {
+                        break;
+                    }
+                }
+                // the client can catch up from the history endpoint
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(user_id = %user_id, skipped, "chat connection fell behind");
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    drop(messages);
+    state.chat.unsubscribe(household_id);
+    info!(user_id = %user_id, household_id = %household_id, "member disconnected from chat");
+}
+
+/// Checks that a connected member is still signed in and still in the household.
+async fn authorize(
+    state: &AppState,
+    household_id: Uuid,
+    current_user: &CurrentUser,
+) -> Result<(), APIError> {
+    check_not_revoked(state, current_user).await?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    Ok(())
+}
+
+/// Saves a message sent over a socket and broadcasts it to the household.
+async fn receive_message(
+    state: &AppState,
+    household_id: Uuid,
+    current_user: &CurrentUser,
+    text: &str,
+) -> Result<(), APIError> {
+    let payload: SendChatMessageRequest = serde_json::from_str(text).map_err(|e| {
+        APIErrorBuilder::new(ValidationError)
+            .cause(e)
+            .detail("The message could not be read.")
+            .build()
+    })?;
+    payload.validate()?;
+
+    // the user may have left the household or been signed out since they connected
+    check_not_revoked(state, current_user).await?;
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let message = create_message(
+        &mut conn,
+        NewChatMessage {
+            household_id,
+            user_id: Some(current_user.id),
+            body: payload.body,
+        },
+    )
+    .await?;
+
+    state.chat.publish(household_id, message.into());
+    Ok(())
+}
+
+async fn send_json(socket: &mut WebSocket, value: &impl Serialize) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(value).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+async fn close(socket: &mut WebSocket, reason: &'static str) {
+    // the connection is ending either way, so there is nothing to do if the client is already gone
+    let _ = socket
+        .send(Message::Close(Some(CloseFrame {
+            code: close_code::POLICY,
+            reason: reason.into(),
+        })))
+        .await;
+}
+
+/// Get the chat history of a household
+///
+/// Returns the newest messages first. Clients that reconnect can page back until they reach a message they have.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/chat/messages",
+    tag = "chat",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ChatHistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "Success", body = ChatHistoryResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn get_chat_history(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    Query(query): Query<ChatHistoryQuery>,
+) -> Result<(StatusCode, Json<ChatHistoryResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    query.validate()?;
+    let before = query
+        .before
+        .map(|id| parse_friendly_id(&id, ItemIdType::ChatMessage))
+        .transpose()?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    // fetch one extra message to find out if there is another page
+    let mut messages = find_messages(&mut conn, household_id, before, query.limit + 1).await?;
+    let next_before = if messages.len() as i64 > query.limit {
+        messages.truncate(query.limit as usize);
+        messages
+            .last()
+            .map(|message| message.id.to_friendly_id(ItemIdType::ChatMessage))
+    } else {
+        None
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(ChatHistoryResponse {
+            messages: messages
+                .into_iter()
+                .map(ChatMessageResponse::from)
+                .collect(),
+            next_before,
+        }),
+    ))
+}
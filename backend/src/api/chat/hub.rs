@@ -0,0 +1,48 @@
+use super::models::ChatMessageResponse;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// How many messages a connection can fall behind by before it starts missing them.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// The broadcast channels of households that have members connected to chat.
+///
+/// A channel is created when the first member of a household connects and dropped when the last one leaves.
+#[derive(Default)]
+pub struct ChatHub {
+    channels: Mutex<HashMap<Uuid, broadcast::Sender<ChatMessageResponse>>>,
+}
+
+impl ChatHub {
+    pub fn subscribe(&self, household_id: Uuid) -> broadcast::Receiver<ChatMessageResponse> {
+        let mut channels = self.channels.lock().unwrap();
+        channels
+            .entry(household_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Drops the channel of a household if no one is listening to it anymore.
+    ///
+    /// Should be called after a receiver from [`ChatHub::subscribe`] is dropped.
+    pub fn unsubscribe(&self, household_id: Uuid) {
+        let mut channels = self.channels.lock().unwrap();
+        if channels
+            .get(&household_id)
+            .is_some_and(|sender| sender.receiver_count() == 0)
+        {
+            channels.remove(&household_id);
+        }
+    }
+
+    /// Sends a message to every member of a household that is connected.
+    pub fn publish(&self, household_id: Uuid, message: ChatMessageResponse) {
+        let channels = self.channels.lock().unwrap();
+        if let Some(sender) = channels.get(&household_id) {
+            // only fails if there are no receivers, in which case no one needs the message
+            let _ = sender.send(message);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::get;
+use axum::{middleware, Router};
+use controllers::{chat_socket, get_chat_history};
+
+pub mod controllers;
+pub mod hub;
+pub mod models;
+mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/messages", get(get_chat_history))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+        // authenticated during the handshake instead, since browsers can't set headers on sockets
+        .route("/ws", get(chat_socket))
+}
@@ -0,0 +1,79 @@
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::chat_message::ChatMessage;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+/// A message sent by a client over the chat socket.
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct SendChatMessageRequest {
+    #[validate(length(min = 1, max = 2000))]
+    #[schema(
+        example = "Who took the last of the milk?",
+        min_length = 1,
+        max_length = 2000
+    )]
+    pub body: String,
+}
+
+#[derive(Serialize, ToSchema, Clone, Debug)]
+pub struct ChatMessageResponse {
+    #[schema(example = "chat_message|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    /// The member who sent the message. Missing if their account has been deleted.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub author_id: Option<String>,
+    #[schema(example = "Who took the last of the milk?")]
+    pub body: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<ChatMessage> for ChatMessageResponse {
+    fn from(message: ChatMessage) -> Self {
+        Self {
+            id: message.id.to_friendly_id(ItemIdType::ChatMessage),
+            household_id: message.household_id.to_friendly_id(ItemIdType::Household),
+            author_id: message
+                .user_id
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            body: message.body,
+            created_at: message.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct ChatSocketQuery {
+    /// An access token, for clients that can't set the `Authorization` header on WebSocket requests.
+    pub token: Option<String>,
+}
+
+#[derive(Deserialize, Validate, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct ChatHistoryQuery {
+    /// Only return messages sent before this message. Used to fetch the next page.
+    #[param(example = "chat_message|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub before: Option<String>,
+
+    /// How many messages to return.
+    #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 100))]
+    #[param(minimum = 1, maximum = 100, default = 50)]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChatHistoryResponse {
+    /// The newest messages first.
+    pub messages: Vec<ChatMessageResponse>,
+    /// Pass as `before` to fetch the next page. Missing if there are no older messages.
+    #[schema(example = "chat_message|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub next_before: Option<String>,
+}
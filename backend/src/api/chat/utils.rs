@@ -0,0 +1,65 @@
+use crate::api::error::APIError;
+use crate::api::utils::validation::field_error;
+use crate::db::chat_message::{ChatMessage, NewChatMessage};
+use crate::db::database::Connection;
+use crate::db::schema::chat_messages;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn create_message(
+    conn: &mut Connection,
+    message: NewChatMessage,
+) -> Result<ChatMessage, APIError> {
+    diesel::insert_into(chat_messages::table)
+        .values(&message)
+        .returning(ChatMessage::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds a page of the messages of a household, newest first.
+///
+/// If `before` is given, only messages older than that message are returned.
+pub async fn find_messages(
+    conn: &mut Connection,
+    household_id: Uuid,
+    before: Option<Uuid>,
+    limit: i64,
+) -> Result<Vec<ChatMessage>, APIError> {
+    let mut query = ChatMessage::all()
+        .filter(chat_messages::household_id.eq(household_id))
+        .order((chat_messages::created_at.desc(), chat_messages::id.desc()))
+        .limit(limit)
+        .into_boxed();
+
+    if let Some(before) = before {
+        let (created_at, id) = find_cursor(conn, household_id, before).await?;
+        query = query.filter(
+            chat_messages::created_at
+                .lt(created_at)
+                .or(chat_messages::created_at
+                    .eq(created_at)
+                    .and(chat_messages::id.lt(id))),
+        );
+    }
+
+    query.load(conn).await.map_err(APIError::from)
+}
+
+async fn find_cursor(
+    conn: &mut Connection,
+    household_id: Uuid,
+    message_id: Uuid,
+) -> Result<(chrono::NaiveDateTime, Uuid), APIError> {
+    chat_messages::table
+        .filter(chat_messages::id.eq(message_id))
+        .filter(chat_messages::household_id.eq(household_id))
+        .select((chat_messages::created_at, chat_messages::id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| field_error("before", "not_found", "The message could not be found."))
+}
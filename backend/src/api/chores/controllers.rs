@@ -0,0 +1,522 @@
+use super::models::{
+    ChoreOccurrenceResponse, ChoreResponse, CreateChoreRequest, ReassignOccurrenceRequest,
+    ScoreboardQuery, ScoreboardResponse, UpdateChoreRequest,
+};
+use super::utils::{
+    create_chore as insert_chore, delete_chore as remove_chore, find_chore,
+    find_chores_for_household, find_occurrences_for_chore, find_pending_occurrence,
+    find_pending_occurrences, find_scoreboard, next_occurrence, rank_scoreboard,
+    reassign_occurrence as save_assignee, resolve_occurrence, scoreboard_window,
+    update_chore as save_chore,
+};
+use crate::api::error::ErrorType::Forbidden;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::{
+    find_household_for_user, find_household_member_ids, parse_member_id,
+};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::{Path, Query};
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::ValidatedJson;
+use crate::db::chore::{ChoreChangeset, NewChore, OccurrenceStatus};
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use tracing::info;
+
+/// Create a new chore
+///
+/// The first occurrence of the chore is scheduled straight away.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/chores",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateChoreRequest
+    ),
+    responses(
+        (status = 201, description = "Created new chore successfully", body = ChoreResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_chore(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateChoreRequest>,
+) -> Result<(StatusCode, Json<ChoreResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let assignee_id = match payload.assignee_id {
+        Some(ref assignee_id) => parse_member_id(assignee_id, &member_ids, "assignee_id")?,
+        None => current_user.id,
+    };
+
+    let start_date = payload
+        .start_date
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let first_due_date = payload.recurrence.first_due_date(start_date);
+    let (recurrence, recurrence_interval, recurrence_weekdays) = payload.recurrence.to_columns();
+
+    info!(user_id = %current_user.id, household_id = %household_id, "creating chore");
+    let (chore, occurrence) = insert_chore(
+        &mut conn,
+        NewChore {
+            household_id,
+            created_by: Some(current_user.id),
+            name: payload.name,
+            description: payload.description,
+            recurrence,
+            recurrence_interval,
+            recurrence_weekdays,
+            rotate: payload.rotate,
+            start_date,
+            effort: payload.effort,
+        },
+        first_due_date,
+        assignee_id,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json((chore, Some(occurrence)).into())))
+}
+
+/// List the chores of a household
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/chores",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [ChoreResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_chores(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<ChoreResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let chores = find_chores_for_household(&mut conn, household_id).await?;
+    let chore_ids: Vec<_> = chores.iter().map(|chore| chore.id).collect();
+    let mut pending = find_pending_occurrences(&mut conn, &chore_ids).await?;
+
+    let response = chores
+        .into_iter()
+        .map(|chore| {
+            let next = pending
+                .iter()
+                .position(|occurrence| occurrence.chore_id == chore.id)
+                .map(|index| pending.swap_remove(index));
+            ChoreResponse::from((chore, next))
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a chore
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/chores/{chore_id}",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = ChoreResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or chore not found", body = APIError),
+    )
+)]
+pub async fn get_chore(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, chore_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<ChoreResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let chore_id = parse_friendly_id(&chore_id, ItemIdType::Chore)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let chore = find_chore(&mut conn, household_id, chore_id).await?;
+    let next = find_pending_occurrences(&mut conn, &[chore.id])
+        .await?
+        .pop();
+
+    Ok((StatusCode::OK, Json((chore, next).into())))
+}
+
+/// Update a chore
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/chores/{chore_id}",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateChoreRequest
+    ),
+    responses(
+        (status = 200, description = "Updated chore successfully", body = ChoreResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or chore not found", body = APIError),
+    )
+)]
+pub async fn update_chore(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, chore_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateChoreRequest>,
+) -> Result<(StatusCode, Json<ChoreResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let chore_id = parse_friendly_id(&chore_id, ItemIdType::Chore)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let chore = find_chore(&mut conn, household_id, chore_id).await?;
+
+    let mut changes = ChoreChangeset {
+        name: payload.name,
+        description: payload.description,
+        rotate: payload.rotate,
+        effort: payload.effort,
+        ..Default::default()
+    };
+    if let Some(recurrence) = payload.recurrence {
+        let (kind, interval, weekdays) = recurrence.to_columns();
+        changes.recurrence = Some(kind);
+        changes.recurrence_interval = Some(interval);
+        changes.recurrence_weekdays = Some(weekdays);
+    }
+
+    let chore = if has_changes(&changes) {
+        save_chore(&mut conn, chore_id, changes).await?
+    } else {
+        chore
+    };
+    let next = find_pending_occurrences(&mut conn, &[chore.id])
+        .await?
+        .pop();
+
+    Ok((StatusCode::OK, Json((chore, next).into())))
+}
+
+/// Delete a chore
+///
+/// Only owners, admins and the member who created the chore can delete it. The chore's history is deleted with it.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/chores/{chore_id}",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+    ),
+    responses(
+        (status = 204, description = "Deleted chore successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to delete the chore", body = APIError),
+        (status = 404, description = "Household or chore not found", body = APIError),
+    )
+)]
+pub async fn delete_chore(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, chore_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let chore_id = parse_friendly_id(&chore_id, ItemIdType::Chore)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let chore = find_chore(&mut conn, household_id, chore_id).await?;
+
+    if !membership.role.can_manage() && chore.created_by != Some(current_user.id) {
+        return Err(APIErrorBuilder::new(Forbidden)
+            .detail("Only owners, admins and the creator of a chore can delete it.")
+            .build());
+    }
+
+    info!(user_id = %current_user.id, chore_id = %chore_id, "deleting chore");
+    remove_chore(&mut conn, chore_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the occurrences of a chore
+///
+/// Occurrences are returned newest first, including the one currently pending.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/chores/{chore_id}/occurrences",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = [ChoreOccurrenceResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or chore not found", body = APIError),
+    )
+)]
+pub async fn list_occurrences(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, chore_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<Vec<ChoreOccurrenceResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let chore_id = parse_friendly_id(&chore_id, ItemIdType::Chore)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_chore(&mut conn, household_id, chore_id).await?;
+
+    let occurrences = find_occurrences_for_chore(&mut conn, chore_id).await?;
+    let response = occurrences
+        .into_iter()
+        .map(ChoreOccurrenceResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Complete an occurrence of a chore
+///
+/// The next occurrence is scheduled and, for rotating chores, assigned to the next member.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/chores/{chore_id}/occurrences/{occurrence_id}/complete",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+        ("occurrence_id" = String, Path, description = "The chore occurrence id"),
+    ),
+    responses(
+        (status = 200, description = "Completed occurrence successfully", body = ChoreResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, chore or occurrence not found", body = APIError),
+        (status = 409, description = "Occurrence already completed or skipped", body = APIError),
+    )
+)]
+pub async fn complete_occurrence(
+    state: State<AppState>,
+    current_user: Extension<CurrentUser>,
+    path: Path<(String, String, String)>,
+) -> Result<(StatusCode, Json<ChoreResponse>), APIError> {
+    resolve(state, current_user, path, OccurrenceStatus::Completed).await
+}
+
+/// Skip an occurrence of a chore
+///
+/// The next occurrence is scheduled and stays with the same member.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/chores/{chore_id}/occurrences/{occurrence_id}/skip",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+        ("occurrence_id" = String, Path, description = "The chore occurrence id"),
+    ),
+    responses(
+        (status = 200, description = "Skipped occurrence successfully", body = ChoreResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, chore or occurrence not found", body = APIError),
+        (status = 409, description = "Occurrence already completed or skipped", body = APIError),
+    )
+)]
+pub async fn skip_occurrence(
+    state: State<AppState>,
+    current_user: Extension<CurrentUser>,
+    path: Path<(String, String, String)>,
+) -> Result<(StatusCode, Json<ChoreResponse>), APIError> {
+    resolve(state, current_user, path, OccurrenceStatus::Skipped).await
+}
+
+async fn resolve(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, chore_id, occurrence_id)): Path<(String, String, String)>,
+    status: OccurrenceStatus,
+) -> Result<(StatusCode, Json<ChoreResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let chore_id = parse_friendly_id(&chore_id, ItemIdType::Chore)?;
+    let occurrence_id = parse_friendly_id(&occurrence_id, ItemIdType::ChoreOccurrence)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let chore = find_chore(&mut conn, household_id, chore_id).await?;
+    let occurrence = find_pending_occurrence(&mut conn, chore_id, occurrence_id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let next = next_occurrence(&chore, &occurrence, status, &member_ids);
+
+    info!(user_id = %current_user.id, occurrence_id = %occurrence_id, status = ?status, "resolving chore occurrence");
+    resolve_occurrence(&mut conn, occurrence_id, status, current_user.id, next).await?;
+
+    let next = find_pending_occurrences(&mut conn, &[chore.id])
+        .await?
+        .pop();
+
+    Ok((StatusCode::OK, Json((chore, next).into())))
+}
+
+/// Reassign an occurrence of a chore
+///
+/// Only pending occurrences can be reassigned. The rotation continues from the new assignee.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/chores/{chore_id}/occurrences/{occurrence_id}/reassign",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("chore_id" = String, Path, description = "The chore id"),
+        ("occurrence_id" = String, Path, description = "The chore occurrence id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = ReassignOccurrenceRequest
+    ),
+    responses(
+        (status = 200, description = "Reassigned occurrence successfully", body = ChoreOccurrenceResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, chore or occurrence not found", body = APIError),
+        (status = 409, description = "Occurrence already completed or skipped", body = APIError),
+    )
+)]
+pub async fn reassign_occurrence(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, chore_id, occurrence_id)): Path<(String, String, String)>,
+    ValidatedJson(payload): ValidatedJson<ReassignOccurrenceRequest>,
+) -> Result<(StatusCode, Json<ChoreOccurrenceResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let chore_id = parse_friendly_id(&chore_id, ItemIdType::Chore)?;
+    let occurrence_id = parse_friendly_id(&occurrence_id, ItemIdType::ChoreOccurrence)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_chore(&mut conn, household_id, chore_id).await?;
+    find_pending_occurrence(&mut conn, chore_id, occurrence_id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let assignee_id = parse_member_id(&payload.assignee_id, &member_ids, "assignee_id")?;
+
+    info!(user_id = %current_user.id, occurrence_id = %occurrence_id, assignee_id = %assignee_id, "reassigning chore occurrence");
+    let occurrence = save_assignee(&mut conn, occurrence_id, assignee_id).await?;
+
+    Ok((StatusCode::OK, Json(occurrence.into())))
+}
+
+/// Get the chore scoreboard of a household
+///
+/// Ranks every member by the chore occurrences they completed in the given period. When `weighted` is set,
+/// members are ranked by the total effort of those chores instead of how many they completed.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/chores/scoreboard",
+    tag = "chores",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ScoreboardQuery,
+    ),
+    responses(
+        (status = 200, description = "Success", body = ScoreboardResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn get_scoreboard(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    Query(query): Query<ScoreboardQuery>,
+) -> Result<(StatusCode, Json<ScoreboardResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let (from, to) = scoreboard_window(&query, chrono::Utc::now().date_naive())?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let rows = find_scoreboard(&mut conn, household_id, from, to, query.weighted).await?;
+
+    let response = ScoreboardResponse {
+        from,
+        to,
+        weighted: query.weighted,
+        entries: rank_scoreboard(rows, query.weighted),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+fn has_changes(changes: &ChoreChangeset) -> bool {
+    changes.name.is_some()
+        || changes.description.is_some()
+        || changes.recurrence.is_some()
+        || changes.recurrence_interval.is_some()
+        || changes.recurrence_weekdays.is_some()
+        || changes.rotate.is_some()
+        || changes.effort.is_some()
+}
@@ -0,0 +1,37 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{get, post};
+use axum::{middleware, Router};
+use controllers::{
+    complete_occurrence, create_chore, delete_chore, get_chore, get_scoreboard, list_chores,
+    list_occurrences, reassign_occurrence, skip_occurrence, update_chore,
+};
+
+pub mod controllers;
+pub mod models;
+pub mod recurrence;
+mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_chore).get(list_chores))
+        .route("/scoreboard", get(get_scoreboard))
+        .route(
+            "/:chore_id",
+            get(get_chore).patch(update_chore).delete(delete_chore),
+        )
+        .route("/:chore_id/occurrences", get(list_occurrences))
+        .route(
+            "/:chore_id/occurrences/:occurrence_id/complete",
+            post(complete_occurrence),
+        )
+        .route(
+            "/:chore_id/occurrences/:occurrence_id/skip",
+            post(skip_occurrence),
+        )
+        .route(
+            "/:chore_id/occurrences/:occurrence_id/reassign",
+            post(reassign_occurrence),
+        )
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
@@ -0,0 +1,203 @@
+use super::recurrence::{validate_recurrence, Recurrence};
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::chore::{Chore, ChoreOccurrence, OccurrenceStatus};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateChoreRequest {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "Take out the bins", min_length = 1, max_length = 64)]
+    pub name: String,
+
+    #[validate(length(max = 1024))]
+    #[schema(example = "Recycling goes out on even weeks.", max_length = 1024)]
+    pub description: Option<String>,
+
+    #[validate(custom = "validate_recurrence")]
+    pub recurrence: Recurrence,
+
+    /// The member the first occurrence is assigned to. Defaults to the current user.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub assignee_id: Option<String>,
+
+    /// Whether the chore rotates to the next member when an occurrence is completed.
+    #[serde(default = "default_rotate")]
+    #[schema(default = true)]
+    pub rotate: bool,
+
+    /// The date of the first occurrence. Defaults to today.
+    pub start_date: Option<chrono::NaiveDate>,
+
+    /// How much work the chore is, used to weight the scoreboard.
+    #[validate(range(min = 1, max = 10))]
+    #[serde(default = "default_effort")]
+    #[schema(example = 2, minimum = 1, maximum = 10, default = 1)]
+    pub effort: i32,
+}
+
+fn default_rotate() -> bool {
+    true
+}
+
+fn default_effort() -> i32 {
+    1
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateChoreRequest {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "Take out the bins", min_length = 1, max_length = 64)]
+    pub name: Option<String>,
+
+    #[validate(length(max = 1024))]
+    #[schema(example = "Recycling goes out on even weeks.", max_length = 1024)]
+    pub description: Option<String>,
+
+    /// Changes only apply to occurrences after the one currently pending.
+    #[validate(custom = "validate_recurrence")]
+    pub recurrence: Option<Recurrence>,
+
+    pub rotate: Option<bool>,
+
+    #[validate(range(min = 1, max = 10))]
+    #[schema(example = 2, minimum = 1, maximum = 10)]
+    pub effort: Option<i32>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct ReassignOccurrenceRequest {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub assignee_id: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChoreResponse {
+    #[schema(example = "chore|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub recurrence: Recurrence,
+    pub rotate: bool,
+    pub effort: i32,
+    pub start_date: chrono::NaiveDate,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    /// The occurrence that currently needs to be done, if any.
+    pub next_occurrence: Option<ChoreOccurrenceResponse>,
+}
+
+impl From<(Chore, Option<ChoreOccurrence>)> for ChoreResponse {
+    fn from((chore, next_occurrence): (Chore, Option<ChoreOccurrence>)) -> Self {
+        Self {
+            id: chore.id.to_friendly_id(ItemIdType::Chore),
+            household_id: chore.household_id.to_friendly_id(ItemIdType::Household),
+            recurrence: Recurrence::from(&chore),
+            name: chore.name,
+            description: chore.description,
+            rotate: chore.rotate,
+            effort: chore.effort,
+            start_date: chore.start_date,
+            created_by: chore
+                .created_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            created_at: chore.created_at,
+            next_occurrence: next_occurrence.map(ChoreOccurrenceResponse::from),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChoreOccurrenceResponse {
+    #[schema(example = "chore_occurrence|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "chore|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub chore_id: String,
+    pub due_date: chrono::NaiveDate,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub assignee_id: Option<String>,
+    pub status: OccurrenceStatus,
+    /// The member who completed or skipped the occurrence.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<ChoreOccurrence> for ChoreOccurrenceResponse {
+    fn from(occurrence: ChoreOccurrence) -> Self {
+        Self {
+            id: occurrence.id.to_friendly_id(ItemIdType::ChoreOccurrence),
+            chore_id: occurrence.chore_id.to_friendly_id(ItemIdType::Chore),
+            due_date: occurrence.due_date,
+            assignee_id: occurrence
+                .assignee_id
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            status: occurrence.status,
+            resolved_by: occurrence
+                .resolved_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            resolved_at: occurrence.resolved_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreboardPeriod {
+    /// Since Monday of the current week.
+    #[default]
+    Week,
+    /// Since the first day of the current month.
+    Month,
+    /// Between the `from` and `to` dates, inclusive.
+    Custom,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct ScoreboardQuery {
+    /// The time window to count completed chores in.
+    #[serde(default)]
+    #[param(inline)]
+    pub period: ScoreboardPeriod,
+
+    /// The first day of a custom period.
+    pub from: Option<chrono::NaiveDate>,
+
+    /// The last day of a custom period.
+    pub to: Option<chrono::NaiveDate>,
+
+    /// Whether scores are weighted by the effort of each chore.
+    #[serde(default)]
+    pub weighted: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ScoreboardResponse {
+    pub from: chrono::NaiveDateTime,
+    pub to: chrono::NaiveDateTime,
+    pub weighted: bool,
+    /// Every member of the household, highest score first.
+    pub entries: Vec<ScoreboardEntry>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ScoreboardEntry {
+    /// Members with the same score share a rank.
+    #[schema(example = 1)]
+    pub rank: usize,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub user_id: String,
+    pub first_name: String,
+    pub last_name: String,
+    /// The number of chore occurrences completed.
+    pub completed: i64,
+    /// The total effort of the chore occurrences completed.
+    pub effort: i64,
+    /// The value members are ranked by.
+    pub score: i64,
+}
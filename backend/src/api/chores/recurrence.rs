@@ -0,0 +1,144 @@
+use crate::db::chore::{Chore, RecurrenceKind};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::ValidationError;
+
+/// How often a chore needs to be done.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// Every day.
+    Daily,
+    /// Every seven days, starting from the start date.
+    Weekly,
+    /// Every `days` days, starting from the start date.
+    EveryNDays { days: u32 },
+    /// On each of the given days of the week.
+    Weekdays { days: Vec<Weekday> },
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    fn bit(&self) -> i32 {
+        1 << (*self as i32)
+    }
+}
+
+impl Recurrence {
+    /// Converts the recurrence into the `(recurrence, recurrence_interval, recurrence_weekdays)` columns of a chore.
+    pub fn to_columns(&self) -> (RecurrenceKind, Option<i32>, Option<i32>) {
+        match self {
+            Recurrence::Daily => (RecurrenceKind::Daily, None, None),
+            Recurrence::Weekly => (RecurrenceKind::Weekly, None, None),
+            Recurrence::EveryNDays { days } => (
+                RecurrenceKind::EveryNDays,
+                Some(i32::try_from(*days).unwrap_or(i32::MAX)),
+                None,
+            ),
+            Recurrence::Weekdays { days } => (
+                RecurrenceKind::Weekdays,
+                None,
+                Some(days.iter().fold(0, |mask, day| mask | day.bit())),
+            ),
+        }
+    }
+
+    /// The date of the first occurrence on or after `start`.
+    pub fn first_due_date(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Weekdays { .. } => self.next_matching_weekday(start - Duration::days(1)),
+            _ => start,
+        }
+    }
+
+    /// The date of the occurrence that follows one due on `previous`.
+    pub fn next_due_date(&self, previous: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Daily => previous + Duration::days(1),
+            Recurrence::Weekly => previous + Duration::days(7),
+            Recurrence::EveryNDays { days } => previous + Duration::days((*days).max(1).into()),
+            Recurrence::Weekdays { .. } => self.next_matching_weekday(previous),
+        }
+    }
+
+    fn next_matching_weekday(&self, after: NaiveDate) -> NaiveDate {
+        let Recurrence::Weekdays { days } = self else {
+            return after + Duration::days(1);
+        };
+
+        (1..=7)
+            .map(|offset| after + Duration::days(offset))
+            .find(|date| days.contains(&date.weekday().into()))
+            .unwrap_or(after + Duration::days(7))
+    }
+}
+
+impl From<&Chore> for Recurrence {
+    fn from(chore: &Chore) -> Self {
+        match chore.recurrence {
+            RecurrenceKind::Daily => Recurrence::Daily,
+            RecurrenceKind::Weekly => Recurrence::Weekly,
+            RecurrenceKind::EveryNDays => Recurrence::EveryNDays {
+                days: chore
+                    .recurrence_interval
+                    .and_then(|days| u32::try_from(days).ok())
+                    .unwrap_or(1),
+            },
+            RecurrenceKind::Weekdays => {
+                let mask = chore.recurrence_weekdays.unwrap_or(0);
+                Recurrence::Weekdays {
+                    days: Weekday::ALL
+                        .into_iter()
+                        .filter(|day| mask & day.bit() != 0)
+                        .collect(),
+                }
+            }
+        }
+    }
+}
+
+pub fn validate_recurrence(recurrence: &Recurrence) -> Result<(), ValidationError> {
+    match recurrence {
+        Recurrence::EveryNDays { days } if *days == 0 || *days > 365 => {
+            Err(ValidationError::new("range"))
+        }
+        Recurrence::Weekdays { days } if days.is_empty() => Err(ValidationError::new("length")),
+        _ => Ok(()),
+    }
+}
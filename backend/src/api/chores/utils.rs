@@ -0,0 +1,393 @@
+use super::models::{ScoreboardEntry, ScoreboardPeriod, ScoreboardQuery};
+use super::recurrence::Recurrence;
+use crate::api::error::ErrorType::{
+    ChoreNotFound, ChoreOccurrenceAlreadyResolved, ChoreOccurrenceNotFound,
+};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::api::utils::validation::field_error;
+use crate::db::chore::{
+    Chore, ChoreChangeset, ChoreOccurrence, NewChore, NewChoreOccurrence, OccurrenceStatus,
+};
+use crate::db::database::Connection;
+use crate::db::schema::{chore_occurrences, chores, household_members, users};
+use crate::db::user::User;
+use chrono::{Datelike, Duration, NaiveTime};
+use diesel::dsl::{count, now};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+/// Picks the member that follows `current` in the rotation.
+///
+/// Members are rotated in the order they joined the household. If `current` is no longer a member,
+/// the rotation starts again from the first member.
+pub fn next_assignee(member_ids: &[Uuid], current: Option<Uuid>) -> Option<Uuid> {
+    let next_index = current
+        .and_then(|current| member_ids.iter().position(|id| *id == current))
+        .map_or(0, |index| (index + 1) % member_ids.len());
+
+    member_ids.get(next_index).copied()
+}
+
+/// Creates a chore along with its first occurrence.
+pub async fn create_chore(
+    conn: &mut Connection,
+    chore: NewChore,
+    first_due_date: chrono::NaiveDate,
+    assignee_id: Uuid,
+) -> Result<(Chore, ChoreOccurrence), APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let chore = diesel::insert_into(chores::table)
+                .values(&chore)
+                .returning(Chore::as_returning())
+                .get_result(conn)
+                .await?;
+
+            let occurrence = diesel::insert_into(chore_occurrences::table)
+                .values(&NewChoreOccurrence {
+                    chore_id: chore.id,
+                    due_date: first_due_date,
+                    assignee_id: Some(assignee_id),
+                })
+                .returning(ChoreOccurrence::as_returning())
+                .get_result(conn)
+                .await?;
+
+            Ok((chore, occurrence))
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+pub async fn find_chore(
+    conn: &mut Connection,
+    household_id: Uuid,
+    chore_id: Uuid,
+) -> Result<Chore, APIError> {
+    Chore::all()
+        .filter(chores::id.eq(chore_id))
+        .filter(chores::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(ChoreNotFound).build())
+}
+
+pub async fn find_chores_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Chore>, APIError> {
+    Chore::all()
+        .filter(chores::household_id.eq(household_id))
+        .order(chores::created_at.asc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the earliest pending occurrence of each of the given chores.
+pub async fn find_pending_occurrences(
+    conn: &mut Connection,
+    chore_ids: &[Uuid],
+) -> Result<Vec<ChoreOccurrence>, APIError> {
+    ChoreOccurrence::all()
+        .filter(chore_occurrences::chore_id.eq_any(chore_ids))
+        .filter(chore_occurrences::status.eq(OccurrenceStatus::Pending))
+        .distinct_on(chore_occurrences::chore_id)
+        .order((
+            chore_occurrences::chore_id,
+            chore_occurrences::due_date.asc(),
+        ))
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn find_occurrences_for_chore(
+    conn: &mut Connection,
+    chore_id: Uuid,
+) -> Result<Vec<ChoreOccurrence>, APIError> {
+    ChoreOccurrence::all()
+        .filter(chore_occurrences::chore_id.eq(chore_id))
+        .order(chore_occurrences::due_date.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds an occurrence of a chore that has not yet been completed or skipped.
+pub async fn find_pending_occurrence(
+    conn: &mut Connection,
+    chore_id: Uuid,
+    occurrence_id: Uuid,
+) -> Result<ChoreOccurrence, APIError> {
+    let occurrence = ChoreOccurrence::all()
+        .filter(chore_occurrences::id.eq(occurrence_id))
+        .filter(chore_occurrences::chore_id.eq(chore_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(ChoreOccurrenceNotFound).build())?;
+
+    if occurrence.status != OccurrenceStatus::Pending {
+        return Err(APIErrorBuilder::new(ChoreOccurrenceAlreadyResolved).build());
+    }
+
+    Ok(occurrence)
+}
+
+pub async fn update_chore(
+    conn: &mut Connection,
+    chore_id: Uuid,
+    changes: ChoreChangeset,
+) -> Result<Chore, APIError> {
+    diesel::update(chores::table.find(chore_id))
+        .set(&changes)
+        .returning(Chore::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn delete_chore(conn: &mut Connection, chore_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(chores::table.find(chore_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Marks a pending occurrence as completed or skipped and schedules the next one.
+///
+/// Returns the resolved occurrence and the newly scheduled occurrence.
+pub async fn resolve_occurrence(
+    conn: &mut Connection,
+    occurrence_id: Uuid,
+    status: OccurrenceStatus,
+    resolved_by: Uuid,
+    next: NewChoreOccurrence,
+) -> Result<(ChoreOccurrence, Option<ChoreOccurrence>), APIError> {
+    let resolved = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                // Only pending occurrences are updated, so two members resolving the same occurrence can't both win.
+                let resolved = diesel::update(
+                    chore_occurrences::table
+                        .filter(chore_occurrences::id.eq(occurrence_id))
+                        .filter(chore_occurrences::status.eq(OccurrenceStatus::Pending)),
+                )
+                .set((
+                    chore_occurrences::status.eq(status),
+                    chore_occurrences::resolved_by.eq(resolved_by),
+                    chore_occurrences::resolved_at.eq(now.nullable()),
+                ))
+                .returning(ChoreOccurrence::as_returning())
+                .get_result(conn)
+                .await
+                .optional()?;
+
+                let Some(resolved) = resolved else {
+                    return Ok(None);
+                };
+
+                let next = diesel::insert_into(chore_occurrences::table)
+                    .values(&next)
+                    .on_conflict((chore_occurrences::chore_id, chore_occurrences::due_date))
+                    .do_nothing()
+                    .returning(ChoreOccurrence::as_returning())
+                    .get_result(conn)
+                    .await
+                    .optional()?;
+
+                Ok(Some((resolved, next)))
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(APIError::from)?;
+
+    resolved.ok_or_else(|| APIErrorBuilder::new(ChoreOccurrenceAlreadyResolved).build())
+}
+
+pub async fn reassign_occurrence(
+    conn: &mut Connection,
+    occurrence_id: Uuid,
+    assignee_id: Uuid,
+) -> Result<ChoreOccurrence, APIError> {
+    diesel::update(
+        chore_occurrences::table
+            .filter(chore_occurrences::id.eq(occurrence_id))
+            .filter(chore_occurrences::status.eq(OccurrenceStatus::Pending)),
+    )
+    .set(chore_occurrences::assignee_id.eq(assignee_id))
+    .returning(ChoreOccurrence::as_returning())
+    .get_result(conn)
+    .await
+    .optional()
+    .map_err(APIError::from)?
+    .ok_or_else(|| APIErrorBuilder::new(ChoreOccurrenceAlreadyResolved).build())
+}
+
+/// Builds the occurrence that follows `occurrence` once it has been resolved with `status`.
+///
+/// Completed occurrences of rotating chores are handed to the next member, otherwise the chore stays with the
+/// same member for as long as they remain in the household.
+pub fn next_occurrence(
+    chore: &Chore,
+    occurrence: &ChoreOccurrence,
+    status: OccurrenceStatus,
+    member_ids: &[Uuid],
+) -> NewChoreOccurrence {
+    let recurrence = Recurrence::from(chore);
+    let still_member = occurrence
+        .assignee_id
+        .filter(|assignee_id| member_ids.contains(assignee_id));
+
+    let assignee_id = match (status, chore.rotate, still_member) {
+        (OccurrenceStatus::Completed, true, _) => next_assignee(member_ids, occurrence.assignee_id),
+        (_, _, Some(assignee_id)) => Some(assignee_id),
+        (_, _, None) => next_assignee(member_ids, None),
+    };
+
+    NewChoreOccurrence {
+        chore_id: chore.id,
+        due_date: recurrence.next_due_date(occurrence.due_date),
+        assignee_id,
+    }
+}
+
+/// A member's totals from [`find_scoreboard`], as `(user, completed, effort)`.
+pub type ScoreboardRow = (User, i64, Option<i64>);
+
+/// Totals up the chore occurrences each member of the household completed between `from` and `to`.
+///
+/// Every member is included, even those who completed nothing. Rows are ordered by score, highest first.
+pub async fn find_scoreboard(
+    conn: &mut Connection,
+    household_id: Uuid,
+    from: chrono::NaiveDateTime,
+    to: chrono::NaiveDateTime,
+    weighted: bool,
+) -> Result<Vec<ScoreboardRow>, APIError> {
+    let household_chores = chores::table
+        .filter(chores::household_id.eq(household_id))
+        .select(chores::id);
+
+    let query = household_members::table
+        .inner_join(users::table)
+        .left_join(
+            chore_occurrences::table.on(chore_occurrences::resolved_by
+                .eq(household_members::user_id.nullable())
+                .and(chore_occurrences::status.eq(OccurrenceStatus::Completed))
+                .and(chore_occurrences::resolved_at.ge(from))
+                .and(chore_occurrences::resolved_at.lt(to))
+                .and(chore_occurrences::chore_id.eq_any(household_chores))),
+        )
+        .left_join(chores::table.on(chores::id.eq(chore_occurrences::chore_id)))
+        .filter(household_members::household_id.eq(household_id))
+        .group_by(users::id)
+        .select((
+            User::as_select(),
+            count(chore_occurrences::id.nullable()),
+            diesel::dsl::sum(chores::effort.nullable()),
+        ))
+        .into_boxed();
+
+    let query = if weighted {
+        query.order_by(
+            diesel::dsl::sum(chores::effort.nullable())
+                .desc()
+                .nulls_last(),
+        )
+    } else {
+        query.order_by(count(chore_occurrences::id.nullable()).desc())
+    };
+
+    query
+        .then_order_by(users::first_name.asc())
+        .then_order_by(users::id.asc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Works out the `[from, to)` window a scoreboard covers.
+pub fn scoreboard_window(
+    query: &ScoreboardQuery,
+    today: chrono::NaiveDate,
+) -> Result<(chrono::NaiveDateTime, chrono::NaiveDateTime), APIError> {
+    let tomorrow = today
+        .succ_opt()
+        .ok_or_else(|| APIErrorBuilder::from_error("today is the last representable date").build());
+
+    let (from, to) = match query.period {
+        ScoreboardPeriod::Week => (
+            today - Duration::days(today.weekday().num_days_from_monday().into()),
+            tomorrow?,
+        ),
+        ScoreboardPeriod::Month => (today.with_day(1).unwrap_or(today), tomorrow?),
+        ScoreboardPeriod::Custom => {
+            let (Some(from), Some(to)) = (query.from, query.to) else {
+                return Err(field_error(
+                    "period",
+                    "custom_range",
+                    "A custom period needs both a from and a to date.",
+                ));
+            };
+
+            if from > to {
+                return Err(field_error(
+                    "from",
+                    "range",
+                    "The from date must not be after the to date.",
+                ));
+            }
+
+            let Some(end) = to.succ_opt() else {
+                return Err(field_error(
+                    "to",
+                    "range",
+                    "The to date is too far in the future.",
+                ));
+            };
+
+            (from, end)
+        }
+    };
+
+    Ok((from.and_time(NaiveTime::MIN), to.and_time(NaiveTime::MIN)))
+}
+
+/// Assigns ranks to rows already ordered by score. Members with equal scores share a rank.
+pub fn rank_scoreboard(rows: Vec<ScoreboardRow>, weighted: bool) -> Vec<ScoreboardEntry> {
+    let mut entries: Vec<ScoreboardEntry> = Vec::with_capacity(rows.len());
+
+    for (position, (user, completed, effort)) in rows.into_iter().enumerate() {
+        let effort = effort.unwrap_or(0);
+        let score = if weighted { effort } else { completed };
+        let rank = match entries.last() {
+            Some(previous) if previous.score == score => previous.rank,
+            _ => position + 1,
+        };
+
+        entries.push(ScoreboardEntry {
+            rank,
+            user_id: user.id.to_friendly_id(ItemIdType::User),
+            first_name: user.first_name,
+            last_name: user.last_name,
+            completed,
+            effort,
+            score,
+        });
+    }
+
+    entries
+}
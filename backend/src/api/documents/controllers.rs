@@ -0,0 +1,389 @@
+use super::models::{DocumentResponse, UpdateDocumentRequest};
+use super::upload::{
+    content_disposition, document_name, limit_size, sniff_content_type, UploadTooLarge,
+};
+use super::utils::{
+    create_document, delete_document as remove_document, find_document,
+    find_documents_for_household, require_document_editor, storage_key,
+    update_document as save_document,
+};
+use crate::api::auth::utils::require_verified_email;
+use crate::api::error::ErrorType::{DocumentTooLarge, ValidationError};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::find_household_for_user;
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::{Multipart, Path};
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::{field_error, ValidatedJson};
+use crate::config::VerifiedAction;
+use crate::db::document::{DocumentChangeset, NewDocument};
+use crate::storage::{ByteStream, StorageError};
+use crate::AppState;
+use axum::body::StreamBody;
+use axum::extract::multipart::MultipartError;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::{Extension, Json};
+use futures::StreamExt;
+use std::io;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Upload a document
+///
+/// The file is sent as the `file` field of a multipart form. Its type is worked out from its contents.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/documents",
+    tag = "documents",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        content = UploadDocumentRequest
+    ),
+    responses(
+        (status = 201, description = "Uploaded document successfully", body = DocumentResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+        (status = 413, description = "Document is too large", body = APIError),
+    )
+)]
+pub async fn upload_document(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    Multipart(mut multipart): Multipart,
+) -> Result<(StatusCode, Json<DocumentResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    require_verified_email(
+        &current_user,
+        &state.settings.verification,
+        VerifiedAction::UploadDocuments,
+    )?;
+
+    // don't hold on to a connection while the file is uploading
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    drop(conn);
+
+    let max_bytes = state.settings.storage.max_upload_bytes;
+    let field = loop {
+        match multipart
+            .next_field()
+            .await
+            .map_err(|e| multipart_error(&e, max_bytes))?
+        {
+            Some(field) if field.name() == Some("file") => break field,
+            Some(_) => continue,
+            None => return Err(field_error("file", "required", "A file is required.")),
+        }
+    };
+
+    let document_id = Uuid::new_v4();
+    let key = storage_key(household_id, document_id);
+    let name = document_name(field.file_name());
+
+    info!(user_id = %current_user.id, household_id = %household_id, document_id = %document_id, "uploading document");
+    let contents: ByteStream = Box::pin(field.map(|chunk| chunk.map_err(io::Error::other)));
+    let (content_type, contents) = sniff_content_type(limit_size(contents, max_bytes))
+        .await
+        .map_err(|e| upload_error(e, max_bytes))?;
+
+    let size = match state.storage.put(&key, contents).await {
+        Ok(size) => size,
+        Err(StorageError::Io(e)) => return Err(upload_error(e, max_bytes)),
+        Err(e) => {
+            return Err(APIErrorBuilder::from_error(e).build());
+        }
+    };
+
+    if size == 0 {
+        discard_contents(&state, &key).await;
+        return Err(field_error("file", "length", "The file is empty."));
+    }
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let document = create_document(
+        &mut conn,
+        NewDocument {
+            id: document_id,
+            household_id,
+            uploaded_by: Some(current_user.id),
+            name,
+            content_type: content_type.to_string(),
+            size_bytes: size as i64,
+            storage_key: key.clone(),
+        },
+    )
+    .await;
+
+    match document {
+        Ok(document) => Ok((StatusCode::CREATED, Json(document.into()))),
+        Err(e) => {
+            discard_contents(&state, &key).await;
+            Err(e)
+        }
+    }
+}
+
+fn multipart_error(error: &MultipartError, max_bytes: u64) -> APIError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return too_large(max_bytes);
+    }
+
+    APIErrorBuilder::new(ValidationError)
+        .cause(error)
+        .detail("The upload is not a valid multipart form.")
+        .build()
+}
+
+fn too_large(max_bytes: u64) -> APIError {
+    APIErrorBuilder::new(DocumentTooLarge)
+        .detail(&format!("Documents can be at most {} bytes.", max_bytes))
+        .build()
+}
+
+/// Reports why an upload failed, which is either the client's fault or a storage failure.
+fn upload_error(error: io::Error, max_bytes: u64) -> APIError {
+    if UploadTooLarge::caused(&error) {
+        return too_large(max_bytes);
+    }
+
+    if let Some(multipart) = error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<MultipartError>())
+    {
+        return multipart_error(multipart, max_bytes);
+    }
+
+    APIErrorBuilder::from_error(error).build()
+}
+
+/// Deletes stored contents that no document refers to.
+async fn discard_contents(state: &AppState, key: &str) {
+    if let Err(e) = state.storage.delete(key).await {
+        warn!(error = %e, key, "failed to delete unused document contents");
+    }
+}
+
+/// List the documents of a household
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/documents",
+    tag = "documents",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [DocumentResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_documents(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<DocumentResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let documents = find_documents_for_household(&mut conn, household_id).await?;
+    Ok((
+        StatusCode::OK,
+        Json(documents.into_iter().map(DocumentResponse::from).collect()),
+    ))
+}
+
+/// Get the details of a document
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/documents/{document_id}",
+    tag = "documents",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("document_id" = String, Path, description = "The document id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = DocumentResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or document not found", body = APIError),
+    )
+)]
+pub async fn get_document(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, document_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<DocumentResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let document_id = parse_friendly_id(&document_id, ItemIdType::Document)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let document = find_document(&mut conn, household_id, document_id).await?;
+    Ok((StatusCode::OK, Json(document.into())))
+}
+
+/// Download a document
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/documents/{document_id}/content",
+    tag = "documents",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("document_id" = String, Path, description = "The document id"),
+    ),
+    responses(
+        (status = 200, description = "The contents of the document", content_type = "application/octet-stream", body = Vec<u8>),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or document not found", body = APIError),
+    )
+)]
+pub async fn download_document(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, document_id)): Path<(String, String)>,
+) -> Result<(StatusCode, HeaderMap, StreamBody<ByteStream<'static>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let document_id = parse_friendly_id(&document_id, ItemIdType::Document)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let document = find_document(&mut conn, household_id, document_id).await?;
+    drop(conn);
+
+    let contents = state
+        .storage
+        .get(&document.storage_key)
+        .await
+        .map_err(|e| APIErrorBuilder::from_error(e).build())?;
+
+    let mut headers = HeaderMap::new();
+    let content_type = HeaderValue::from_str(&document.content_type)
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+    headers.insert(header::CONTENT_TYPE, content_type);
+    headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from(document.size_bytes),
+    );
+    if let Ok(disposition) = HeaderValue::from_str(&content_disposition(&document.name)) {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    // uploads are untrusted, so browsers must not guess a more dangerous type for them
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+
+    Ok((StatusCode::OK, headers, StreamBody::new(contents)))
+}
+
+/// Rename a document
+///
+/// Only owners, admins and the uploader of the document can rename it.
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/documents/{document_id}",
+    tag = "documents",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("document_id" = String, Path, description = "The document id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateDocumentRequest
+    ),
+    responses(
+        (status = 200, description = "Updated document successfully", body = DocumentResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to update the document", body = APIError),
+        (status = 404, description = "Household or document not found", body = APIError),
+    )
+)]
+pub async fn update_document(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, document_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateDocumentRequest>,
+) -> Result<(StatusCode, Json<DocumentResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let document_id = parse_friendly_id(&document_id, ItemIdType::Document)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let document = find_document(&mut conn, household_id, document_id).await?;
+    require_document_editor(&document, &membership, current_user.id)?;
+
+    if payload.name.is_none() {
+        return Ok((StatusCode::OK, Json(document.into())));
+    }
+
+    let changes = DocumentChangeset { name: payload.name };
+    let document = save_document(&mut conn, document_id, changes).await?;
+
+    Ok((StatusCode::OK, Json(document.into())))
+}
+
+/// Delete a document
+///
+/// Only owners, admins and the uploader of the document can delete it.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/documents/{document_id}",
+    tag = "documents",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("document_id" = String, Path, description = "The document id"),
+    ),
+    responses(
+        (status = 204, description = "Deleted document successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to delete the document", body = APIError),
+        (status = 404, description = "Household or document not found", body = APIError),
+    )
+)]
+pub async fn delete_document(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, document_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let document_id = parse_friendly_id(&document_id, ItemIdType::Document)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let document = find_document(&mut conn, household_id, document_id).await?;
+    require_document_editor(&document, &membership, current_user.id)?;
+
+    info!(user_id = %current_user.id, document_id = %document_id, "deleting document");
+    remove_document(&mut conn, document_id).await?;
+    discard_contents(&state, &document.storage_key).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
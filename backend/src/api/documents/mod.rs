@@ -0,0 +1,98 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::extract::DefaultBodyLimit;
+use axum::routing::get;
+use axum::{middleware, Router};
+use controllers::{
+    delete_document, download_document, get_document, list_documents, update_document,
+    upload_document,
+};
+
+pub mod controllers;
+pub mod models;
+mod upload;
+mod utils;
+
+/// Room for the rest of a multipart form around the file being uploaded.
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+/// Lets upload bodies grow to the largest document that can be stored, plus the rest of the form.
+///
+/// Without it the multipart extractor stops at axum's default limit of 2 MiB. The size of the file itself is checked
+/// while it is stored, so the limit can be reported in terms of the document.
+fn upload_body_limit(max_upload_bytes: u64) -> DefaultBodyLimit {
+    DefaultBodyLimit::max(max_upload_bytes as usize + MULTIPART_OVERHEAD_BYTES)
+}
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_documents)
+                .post(upload_document)
+                .layer(upload_body_limit(state.settings.storage.max_upload_bytes)),
+        )
+        .route(
+            "/:document_id",
+            get(get_document)
+                .patch(update_document)
+                .delete(delete_document),
+        )
+        .route("/:document_id/content", get(download_document))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::utils::extract::Multipart;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::post;
+    use tower::ServiceExt;
+
+    const MIB: usize = 1024 * 1024;
+
+    /// Reads every field of a form, answering with how many bytes they held.
+    async fn read_form(Multipart(mut multipart): Multipart) -> Result<String, StatusCode> {
+        let mut size = 0;
+        while let Some(field) = multipart.next_field().await.map_err(|e| e.status())? {
+            size += field.bytes().await.map_err(|e| e.status())?.len();
+        }
+        Ok(size.to_string())
+    }
+
+    fn upload(size: usize) -> Request<Body> {
+        let mut body = b"--boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"big.bin\"\r\n\r\n".to_vec();
+        body.resize(body.len() + size, b'a');
+        body.extend(b"\r\n--boundary--\r\n");
+
+        Request::post("/")
+            .header("content-type", "multipart/form-data; boundary=boundary")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    fn app(max_upload_bytes: u64) -> Router {
+        Router::new().route(
+            "/",
+            post(read_form).layer(upload_body_limit(max_upload_bytes)),
+        )
+    }
+
+    #[tokio::test]
+    async fn accepts_uploads_past_axums_default_limit() {
+        let response = app(25 * MIB as u64).oneshot(upload(3 * MIB)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, (3 * MIB).to_string());
+    }
+
+    #[tokio::test]
+    async fn rejects_uploads_past_the_limit() {
+        let response = app(MIB as u64).oneshot(upload(2 * MIB)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::document::Document;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// The multipart form a document is uploaded with.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct UploadDocumentRequest {
+    /// The contents of the document. Its file name is used as the document name.
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateDocumentRequest {
+    #[validate(length(min = 1, max = 255))]
+    #[schema(example = "Lease.pdf", min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentResponse {
+    #[schema(example = "document|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    #[schema(example = "Lease.pdf")]
+    pub name: String,
+    /// Worked out from the contents of the file, rather than what the uploader claimed.
+    #[schema(example = "application/pdf")]
+    pub content_type: String,
+    #[schema(example = 52_731)]
+    pub size_bytes: i64,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub uploaded_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<Document> for DocumentResponse {
+    fn from(document: Document) -> Self {
+        Self {
+            id: document.id.to_friendly_id(ItemIdType::Document),
+            household_id: document.household_id.to_friendly_id(ItemIdType::Household),
+            name: document.name,
+            content_type: document.content_type,
+            size_bytes: document.size_bytes,
+            uploaded_by: document
+                .uploaded_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            created_at: document.created_at,
+        }
+    }
+}
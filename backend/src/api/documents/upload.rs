@@ -0,0 +1,129 @@
+use crate::storage::ByteStream;
+use bytes::BytesMut;
+use futures::{stream, StreamExt};
+use std::io;
+use thiserror::Error;
+
+/// How much of the start of a file is read to work out its type.
+const SNIFF_BYTES: usize = 8192;
+
+/// The longest a document name can be, in characters.
+const MAX_NAME_LENGTH: usize = 255;
+
+#[derive(Error, Debug)]
+#[error("The upload is larger than {0} bytes.")]
+pub struct UploadTooLarge(pub u64);
+
+impl UploadTooLarge {
+    /// Checks if an upload failed because it went over the size limit.
+    pub fn caused(error: &io::Error) -> bool {
+        error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<UploadTooLarge>())
+    }
+}
+
+/// Fails the upload with [`UploadTooLarge`] once more than `max_bytes` have been read.
+pub fn limit_size(contents: ByteStream<'_>, max_bytes: u64) -> ByteStream<'_> {
+    let mut size = 0u64;
+    Box::pin(contents.map(move |chunk| {
+        let chunk = chunk?;
+        size += chunk.len() as u64;
+        if size > max_bytes {
+            return Err(io::Error::other(UploadTooLarge(max_bytes)));
+        }
+        Ok(chunk)
+    }))
+}
+
+/// Reads the start of an upload to work out its content type.
+///
+/// Returns the type along with a stream of the whole upload, including the part that was read.
+pub async fn sniff_content_type(
+    mut contents: ByteStream<'_>,
+) -> io::Result<(&'static str, ByteStream<'_>)> {
+    let mut head = BytesMut::new();
+    while head.len() < SNIFF_BYTES {
+        match contents.next().await {
+            Some(chunk) => head.extend_from_slice(&chunk?),
+            None => break,
+        }
+    }
+
+    let head = head.freeze();
+    let content_type = detect_content_type(&head);
+
+    Ok((
+        content_type,
+        Box::pin(stream::once(async move { Ok(head) }).chain(contents)),
+    ))
+}
+
+fn detect_content_type(head: &[u8]) -> &'static str {
+    if let Some(kind) = infer::get(head) {
+        return kind.mime_type();
+    }
+
+    if looks_like_text(head) {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// Plain text has no signature, so anything that is valid UTF-8 without null bytes is treated as text.
+fn looks_like_text(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return false;
+    }
+
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // the head can end part way through a character
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// Turns the file name sent by the client into a document name.
+///
+/// Some browsers send the full path of the file, so only the last component is kept.
+pub fn document_name(file_name: Option<&str>) -> String {
+    let name = file_name
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .map(|name| name.trim().replace(char::is_control, ""))
+        .unwrap_or_default();
+
+    if name.is_empty() {
+        return "Untitled".to_string();
+    }
+
+    name.chars().take(MAX_NAME_LENGTH).collect()
+}
+
+/// Builds a `Content-Disposition` header that downloads a file with the given name.
+///
+/// The name is given both as a plain ASCII fallback and percent-encoded for clients that support RFC 6266.
+pub fn content_disposition(name: &str) -> String {
+    let fallback: String = name
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+
+    let mut encoded = String::new();
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
@@ -0,0 +1,90 @@
+use crate::api::error::ErrorType::{DocumentNotFound, Forbidden};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::db::database::Connection;
+use crate::db::document::{Document, DocumentChangeset, NewDocument};
+use crate::db::household::HouseholdMember;
+use crate::db::schema::documents;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+/// The key the contents of a document are kept under in storage.
+pub fn storage_key(household_id: Uuid, document_id: Uuid) -> String {
+    format!("{}/{}", household_id, document_id)
+}
+
+pub async fn create_document(
+    conn: &mut Connection,
+    document: NewDocument,
+) -> Result<Document, APIError> {
+    diesel::insert_into(documents::table)
+        .values(&document)
+        .returning(Document::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn find_document(
+    conn: &mut Connection,
+    household_id: Uuid,
+    document_id: Uuid,
+) -> Result<Document, APIError> {
+    Document::all()
+        .filter(documents::id.eq(document_id))
+        .filter(documents::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(DocumentNotFound).build())
+}
+
+pub async fn find_documents_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Document>, APIError> {
+    Document::all()
+        .filter(documents::household_id.eq(household_id))
+        .order(documents::created_at.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn update_document(
+    conn: &mut Connection,
+    document_id: Uuid,
+    changes: DocumentChangeset,
+) -> Result<Document, APIError> {
+    diesel::update(documents::table.find(document_id))
+        .set(&changes)
+        .returning(Document::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn delete_document(conn: &mut Connection, document_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(documents::table.find(document_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Ensures the current user can change a document. Owners, admins and the uploader of a document can.
+pub fn require_document_editor(
+    document: &Document,
+    membership: &HouseholdMember,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    if membership.role.can_manage() || document.uploaded_by == Some(user_id) {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(Forbidden)
+        .detail("Only owners, admins and the uploader of a document can change it.")
+        .build())
+}
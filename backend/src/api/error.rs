@@ -1,14 +1,17 @@
+use super::middleware::{causes_exposed, current_request_id};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use const_format::concatcp;
+use diesel::result::{DatabaseErrorInformation, DatabaseErrorKind};
 use serde::{Serialize, Serializer};
-use serde_json::Value;
+use serde_json::{json, Map, Value};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use thiserror::Error;
-use tracing::error;
+use tracing::{error, info};
 use utoipa::ToSchema;
+use validator::{ValidationErrors, ValidationErrorsKind};
 
 const ERROR_URI: &str = "tag:domus@jacksonc.dev,2023:errors/";
 
@@ -24,17 +27,107 @@ pub enum ErrorType {
     #[error("Your request is not valid.")]
     ValidationError,
 
+    #[error("The requested resource could not be found.")]
+    NotFound,
+
+    #[error("The request method is not supported for this resource.")]
+    MethodNotAllowed,
+
+    #[error("The request conflicts with the current state of the resource.")]
+    Conflict,
+
+    #[error("The service is temporarily unavailable.")]
+    ServiceUnavailable,
+
     #[error("A user with that email already exists.")]
     UserAlreadyExists,
 
     #[error("Login Incorrect.")]
     LoginIncorrect,
 
+    #[error("The password you entered is incorrect.")]
+    PasswordIncorrect,
+
     #[error("You have not been authorized to perform this action.")]
     Unauthorized,
 
     #[error("You are not allowed to perform this action.")]
     Forbidden,
+
+    #[error("The household could not be found.")]
+    HouseholdNotFound,
+
+    #[error("You are already a member of this household.")]
+    AlreadyHouseholdMember,
+
+    #[error("The invite could not be found.")]
+    InviteNotFound,
+
+    #[error("The invite has expired.")]
+    InviteExpired,
+
+    #[error("The invite has been revoked.")]
+    InviteRevoked,
+
+    #[error("The invite has already been used.")]
+    InviteAlreadyUsed,
+
+    #[error("The chore could not be found.")]
+    ChoreNotFound,
+
+    #[error("The chore occurrence could not be found.")]
+    ChoreOccurrenceNotFound,
+
+    #[error("The chore occurrence has already been completed or skipped.")]
+    ChoreOccurrenceAlreadyResolved,
+
+    #[error("The bill could not be found.")]
+    BillNotFound,
+
+    #[error("The bill has already been posted.")]
+    BillAlreadyPosted,
+
+    #[error("The recurring bill could not be found.")]
+    BillTemplateNotFound,
+
+    #[error("The settlement could not be found.")]
+    SettlementNotFound,
+
+    #[error("The shopping list could not be found.")]
+    ShoppingListNotFound,
+
+    #[error("The shopping list item could not be found.")]
+    ShoppingItemNotFound,
+
+    #[error("The event could not be found.")]
+    EventNotFound,
+
+    #[error("The document could not be found.")]
+    DocumentNotFound,
+
+    #[error("The document is too large.")]
+    DocumentTooLarge,
+
+    #[error("The session could not be found.")]
+    SessionNotFound,
+
+    #[error("The refresh token has already been used.")]
+    RefreshTokenReused,
+
+    #[error("The password reset link is invalid or has expired.")]
+    InvalidPasswordResetToken,
+
+    #[error("The email verification link is invalid or has expired.")]
+    InvalidEmailVerificationToken,
+
+    #[error("You need to verify your email address to do this.")]
+    EmailNotVerified,
+
+    #[error("Your email address has already been verified.")]
+    EmailAlreadyVerified,
+
+    #[error("A verification email was sent recently.")]
+    VerificationEmailThrottled,
 }
 
 impl ErrorType {
@@ -42,10 +135,50 @@ impl ErrorType {
         match self {
             ErrorType::Unknown | ErrorType::ForeignError(_) => "about:blank",
             ErrorType::ValidationError => concatcp!(ERROR_URI, "validation-error"),
+            ErrorType::NotFound => concatcp!(ERROR_URI, "not-found"),
+            ErrorType::MethodNotAllowed => concatcp!(ERROR_URI, "method-not-allowed"),
+            ErrorType::Conflict => concatcp!(ERROR_URI, "conflict"),
+            ErrorType::ServiceUnavailable => concatcp!(ERROR_URI, "service-unavailable"),
             ErrorType::UserAlreadyExists => concatcp!(ERROR_URI, "user-already-exists"),
             ErrorType::LoginIncorrect => concatcp!(ERROR_URI, "login-incorrect"),
+            ErrorType::PasswordIncorrect => concatcp!(ERROR_URI, "password-incorrect"),
             ErrorType::Unauthorized => concatcp!(ERROR_URI, "unauthorized"),
             ErrorType::Forbidden => concatcp!(ERROR_URI, "forbidden"),
+            ErrorType::HouseholdNotFound => concatcp!(ERROR_URI, "household-not-found"),
+            ErrorType::AlreadyHouseholdMember => concatcp!(ERROR_URI, "already-household-member"),
+            ErrorType::InviteNotFound => concatcp!(ERROR_URI, "invite-not-found"),
+            ErrorType::InviteExpired => concatcp!(ERROR_URI, "invite-expired"),
+            ErrorType::InviteRevoked => concatcp!(ERROR_URI, "invite-revoked"),
+            ErrorType::InviteAlreadyUsed => concatcp!(ERROR_URI, "invite-already-used"),
+            ErrorType::ChoreNotFound => concatcp!(ERROR_URI, "chore-not-found"),
+            ErrorType::ChoreOccurrenceNotFound => {
+                concatcp!(ERROR_URI, "chore-occurrence-not-found")
+            }
+            ErrorType::ChoreOccurrenceAlreadyResolved => {
+                concatcp!(ERROR_URI, "chore-occurrence-already-resolved")
+            }
+            ErrorType::BillNotFound => concatcp!(ERROR_URI, "bill-not-found"),
+            ErrorType::BillAlreadyPosted => concatcp!(ERROR_URI, "bill-already-posted"),
+            ErrorType::BillTemplateNotFound => concatcp!(ERROR_URI, "bill-template-not-found"),
+            ErrorType::SettlementNotFound => concatcp!(ERROR_URI, "settlement-not-found"),
+            ErrorType::ShoppingListNotFound => concatcp!(ERROR_URI, "shopping-list-not-found"),
+            ErrorType::ShoppingItemNotFound => concatcp!(ERROR_URI, "shopping-item-not-found"),
+            ErrorType::EventNotFound => concatcp!(ERROR_URI, "event-not-found"),
+            ErrorType::DocumentNotFound => concatcp!(ERROR_URI, "document-not-found"),
+            ErrorType::DocumentTooLarge => concatcp!(ERROR_URI, "document-too-large"),
+            ErrorType::SessionNotFound => concatcp!(ERROR_URI, "session-not-found"),
+            ErrorType::RefreshTokenReused => concatcp!(ERROR_URI, "refresh-token-reused"),
+            ErrorType::InvalidPasswordResetToken => {
+                concatcp!(ERROR_URI, "invalid-password-reset-token")
+            }
+            ErrorType::InvalidEmailVerificationToken => {
+                concatcp!(ERROR_URI, "invalid-email-verification-token")
+            }
+            ErrorType::EmailNotVerified => concatcp!(ERROR_URI, "email-not-verified"),
+            ErrorType::EmailAlreadyVerified => concatcp!(ERROR_URI, "email-already-verified"),
+            ErrorType::VerificationEmailThrottled => {
+                concatcp!(ERROR_URI, "verification-email-throttled")
+            }
         }
     }
 
@@ -57,16 +190,57 @@ impl ErrorType {
         match self {
             ErrorType::Unknown | ErrorType::ForeignError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ErrorType::ValidationError => StatusCode::BAD_REQUEST,
+            ErrorType::NotFound => StatusCode::NOT_FOUND,
+            ErrorType::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorType::Conflict => StatusCode::CONFLICT,
+            ErrorType::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
             ErrorType::UserAlreadyExists => StatusCode::CONFLICT,
             ErrorType::LoginIncorrect => StatusCode::UNAUTHORIZED,
+            ErrorType::PasswordIncorrect => StatusCode::FORBIDDEN,
             ErrorType::Unauthorized => StatusCode::UNAUTHORIZED,
             ErrorType::Forbidden => StatusCode::FORBIDDEN,
+            ErrorType::HouseholdNotFound => StatusCode::NOT_FOUND,
+            ErrorType::AlreadyHouseholdMember => StatusCode::CONFLICT,
+            ErrorType::InviteNotFound => StatusCode::NOT_FOUND,
+            ErrorType::InviteExpired => StatusCode::GONE,
+            ErrorType::InviteRevoked => StatusCode::GONE,
+            ErrorType::InviteAlreadyUsed => StatusCode::GONE,
+            ErrorType::ChoreNotFound => StatusCode::NOT_FOUND,
+            ErrorType::ChoreOccurrenceNotFound => StatusCode::NOT_FOUND,
+            ErrorType::ChoreOccurrenceAlreadyResolved => StatusCode::CONFLICT,
+            ErrorType::BillNotFound => StatusCode::NOT_FOUND,
+            ErrorType::BillAlreadyPosted => StatusCode::CONFLICT,
+            ErrorType::BillTemplateNotFound => StatusCode::NOT_FOUND,
+            ErrorType::SettlementNotFound => StatusCode::NOT_FOUND,
+            ErrorType::ShoppingListNotFound => StatusCode::NOT_FOUND,
+            ErrorType::ShoppingItemNotFound => StatusCode::NOT_FOUND,
+            ErrorType::EventNotFound => StatusCode::NOT_FOUND,
+            ErrorType::DocumentNotFound => StatusCode::NOT_FOUND,
+            ErrorType::DocumentTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorType::SessionNotFound => StatusCode::NOT_FOUND,
+            ErrorType::RefreshTokenReused => StatusCode::UNAUTHORIZED,
+            ErrorType::InvalidPasswordResetToken => StatusCode::BAD_REQUEST,
+            ErrorType::InvalidEmailVerificationToken => StatusCode::BAD_REQUEST,
+            ErrorType::EmailNotVerified => StatusCode::FORBIDDEN,
+            ErrorType::EmailAlreadyVerified => StatusCode::CONFLICT,
+            ErrorType::VerificationEmailThrottled => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 
     pub fn get_detail(&self) -> Option<&'static str> {
         match self {
+            ErrorType::UserAlreadyExists => Some("If the account is yours, log in to it instead."),
+            ErrorType::ServiceUnavailable => Some("Please try again shortly."),
             ErrorType::LoginIncorrect => Some("The email or password you entered is incorrect. Please check your credentials and try again."),
+            ErrorType::HouseholdNotFound => Some("The household does not exist or you are not a member of it."),
+            ErrorType::InviteExpired => Some("Ask a member of the household to send you a new invite."),
+            ErrorType::InviteRevoked => Some("Ask a member of the household to send you a new invite."),
+            ErrorType::InviteAlreadyUsed => Some("This invite has reached its usage limit. Ask a member of the household to send you a new invite."),
+            ErrorType::InvalidEmailVerificationToken => Some("Ask for a new verification email to try again."),
+            ErrorType::EmailNotVerified => Some("Follow the link in the email we sent you, or ask for a new one."),
+            ErrorType::VerificationEmailThrottled => Some("Wait a minute before asking for another verification email."),
+            ErrorType::InvalidPasswordResetToken => Some("Password reset links can only be used once. Ask for a new one to try again."),
+            ErrorType::RefreshTokenReused => Some("The session has been signed out because its refresh token was used more than once. Please log in again."),
             _ => None,
         }
     }
@@ -103,6 +277,11 @@ pub struct APIError {
     #[serde(skip_serializing_if = "Option::is_none")]
     instance: Option<String>,
 
+    /// The internal error that caused the problem. Only included when the server is configured to expose causes.
+    #[schema(example = "connection refused", value_type = Option<String>)]
+    #[serde(skip_serializing_if = "hide_cause")]
+    cause: Box<Option<String>>,
+
     #[schema(additional_properties = true)]
     #[serde(flatten)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -112,7 +291,8 @@ pub struct APIError {
 impl From<ErrorType> for APIError {
     fn from(value: ErrorType) -> Self {
         if let ErrorType::ForeignError(e) = &value {
-            return APIErrorBuilder::from_error(e).build();
+            // the alternate format includes the whole chain of causes
+            return APIErrorBuilder::from_error(format!("{:#}", e)).build();
         }
 
         APIErrorBuilder::new(value).build()
@@ -125,8 +305,142 @@ impl From<anyhow::Error> for APIError {
     }
 }
 
+impl From<ValidationErrors> for APIError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut fields = Map::new();
+        collect_field_errors(&errors, None, &mut fields);
+
+        APIErrorBuilder::new(ErrorType::ValidationError)
+            .detail("One or more fields are invalid.")
+            .with_field("errors", Value::Object(fields))
+            .build()
+    }
+}
+
+impl From<diesel::result::Error> for APIError {
+    fn from(error: diesel::result::Error) -> Self {
+        use diesel::result::Error;
+
+        match &error {
+            Error::NotFound => APIErrorBuilder::new(ErrorType::NotFound).build(),
+            Error::DatabaseError(kind, info) => match kind {
+                DatabaseErrorKind::UniqueViolation => {
+                    constraint_error(info.as_ref(), "unique", "Is already in use.")
+                        .cause(&error)
+                        .build()
+                }
+                DatabaseErrorKind::ForeignKeyViolation => constraint_error(
+                    info.as_ref(),
+                    "reference",
+                    "Refers to something that doesn't exist or is still in use.",
+                )
+                .cause(&error)
+                .build(),
+                DatabaseErrorKind::SerializationFailure => APIErrorBuilder::new(
+                    ErrorType::Conflict,
+                )
+                .detail(
+                    "The request conflicted with another made at the same time. Please try again.",
+                )
+                .cause(&error)
+                .build(),
+                DatabaseErrorKind::ClosedConnection | DatabaseErrorKind::UnableToSendCommand => {
+                    APIErrorBuilder::new(ErrorType::ServiceUnavailable)
+                        .cause(&error)
+                        .build()
+                }
+                _ => APIErrorBuilder::from_error(&error).build(),
+            },
+            _ => APIErrorBuilder::from_error(&error).build(),
+        }
+    }
+}
+
+/// Describes a constraint that a request broke, naming the field it applies to if it is one a client can set.
+fn constraint_error(
+    info: &dyn DatabaseErrorInformation,
+    code: &str,
+    message: &str,
+) -> APIErrorBuilder {
+    let (error_type, field) = info
+        .constraint_name()
+        .and_then(constraint_violation)
+        .unwrap_or((ErrorType::Conflict, None));
+
+    let builder = APIErrorBuilder::new(error_type);
+    match field {
+        Some(field) => builder.with_field(
+            "errors",
+            json!({ field: [{ "code": code, "message": message, "params": {} }] }),
+        ),
+        None => builder,
+    }
+}
+
+/// The error type and request field of constraints that have their own, for constraints that a request can break.
+fn constraint_violation(constraint: &str) -> Option<(ErrorType, Option<&'static str>)> {
+    match constraint {
+        "users_email_key" => Some((ErrorType::UserAlreadyExists, Some("email"))),
+        "household_members_pkey" => Some((ErrorType::AlreadyHouseholdMember, None)),
+        _ => None,
+    }
+}
+
+/// Flattens nested validation errors into a map from field path (e.g. `split.portions[0].value`) to errors.
+fn collect_field_errors(
+    errors: &ValidationErrors,
+    prefix: Option<&str>,
+    out: &mut Map<String, Value>,
+) {
+    for (field, kind) in errors.errors() {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, field),
+            None => field.to_string(),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                let value = serde_json::to_value(field_errors).unwrap_or_else(|e| {
+                    error!(error = %e, "Failed to serialize validation errors");
+                    Value::Null
+                });
+                out.insert(path, value);
+            }
+            ValidationErrorsKind::Struct(inner) => collect_field_errors(inner, Some(&path), out),
+            ValidationErrorsKind::List(items) => {
+                for (index, inner) in items {
+                    collect_field_errors(inner, Some(&format!("{}[{}]", path, index)), out);
+                }
+            }
+        }
+    }
+}
+
+/// Causes can contain details of the internals of the server, so they are left out unless the server is configured
+/// to expose them.
+fn hide_cause(cause: &Option<String>) -> bool {
+    cause.is_none() || !causes_exposed()
+}
+
+impl APIError {
+    /// Logs the error along with its cause, at error level if it is the server's fault.
+    ///
+    /// This is the one place errors are logged, so errors shouldn't be logged where they happen as well. It is
+    /// logged in the span of the request the error happened in, which carries the id of the request.
+    pub fn log(&self) {
+        let cause = self.cause.as_deref();
+        if self.status.is_server_error() {
+            error!(status = %self.status, error_type = %self.error_type, cause, "request failed");
+        } else {
+            info!(status = %self.status, error_type = %self.error_type, cause, "request rejected");
+        }
+    }
+}
+
 impl IntoResponse for APIError {
     fn into_response(self) -> Response {
+        self.log();
+
         let resp = Response::builder()
             .status(self.status)
             .header("Content-Type", "application/problem+json")
@@ -149,6 +463,7 @@ pub struct APIErrorBuilder {
     error_type: ErrorType,
     detail: Option<String>,
     instance: Option<String>,
+    cause: Option<String>,
     extra: Option<HashMap<String, Value>>,
 }
 
@@ -156,12 +471,20 @@ impl APIErrorBuilder {
     /// Create a new APIErrorBuilder with the given error type.
     ///
     /// This is the recommended way to create an APIErrorBuilder.
+    ///
+    /// The instance of the error is the id of the request being handled, so it can be found in the logs.
     pub fn new(error: ErrorType) -> Self {
-        Self {
+        let builder = Self {
             error_type: error,
             detail: None,
             instance: None,
+            cause: None,
             extra: None,
+        };
+
+        match current_request_id() {
+            Some(request_id) => builder.instance(request_id),
+            None => builder,
         }
     }
 
@@ -172,11 +495,12 @@ impl APIErrorBuilder {
         Self::new(ErrorType::Unknown).cause(error)
     }
 
-    /// Adds a cause field to the error.
+    /// Adds the internal error that caused this one.
     ///
-    /// Shorthand for `with_field("cause", error.to_string().into())`.
-    pub fn cause(self, error: impl Display) -> Self {
-        self.with_field("cause", format!("{}", error).into())
+    /// The cause is logged when the error is returned, but is only included in the response if causes are exposed.
+    pub fn cause(mut self, error: impl Display) -> Self {
+        self.cause = Some(error.to_string());
+        self
     }
 
     /// Add additional information to the error.
@@ -199,7 +523,6 @@ impl APIErrorBuilder {
     /// > When the "instance" URI is dereferenceable, the problem details object can be fetched from it.
     /// >
     /// > When the "instance" URI is not dereferenceable, it serves as a unique identifier for the problem occurrence that may be of significance to the server but is opaque to the client.
-    #[allow(dead_code)] // TODO: remove after first use
     pub fn instance(mut self, instance: String) -> Self {
         self.instance = Some(instance);
         self
@@ -224,6 +547,7 @@ impl APIErrorBuilder {
                 .detail
                 .or(self.error_type.get_detail().map(|s| s.to_string())),
             instance: self.instance,
+            cause: Box::from(self.cause),
             extra: Box::from(self.extra),
             error_type: self.error_type.get_type().to_string(),
         }
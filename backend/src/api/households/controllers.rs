@@ -0,0 +1,317 @@
+use super::models::{
+    CreateHouseholdRequest, HouseholdDetailResponse, HouseholdMemberResponse, HouseholdResponse,
+    UpdateHouseholdRequest, UpdateMemberRequest,
+};
+use super::utils::{
+    create_household as insert_household, delete_household as remove_household,
+    find_household_for_user, find_household_members, find_households_for_user, remove_member,
+    require_manager, update_household as save_household, update_member_role,
+};
+use crate::api::auth::utils::{find_user_by_id, require_verified_email};
+use crate::api::error::ErrorType::Forbidden;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::ValidatedJson;
+use crate::config::VerifiedAction;
+use crate::db::household::{HouseholdChangeset, HouseholdRole, NewHousehold};
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use tracing::info;
+
+/// Create a new household
+///
+/// The current user becomes the owner of the household.
+#[utoipa::path(
+    post,
+    path = "/households",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateHouseholdRequest
+    ),
+    responses(
+        (status = 201, description = "Created new household successfully", body = HouseholdResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+    )
+)]
+pub async fn create_household(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<CreateHouseholdRequest>,
+) -> Result<(StatusCode, Json<HouseholdResponse>), APIError> {
+    require_verified_email(
+        &current_user,
+        &state.settings.verification,
+        VerifiedAction::CreateHousehold,
+    )?;
+
+    info!(user_id = %current_user.id, "creating household");
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let household = insert_household(
+        &mut conn,
+        NewHousehold { name: payload.name },
+        current_user.id,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(household.into())))
+}
+
+/// List the households the current user is a member of
+#[utoipa::path(
+    get,
+    path = "/households",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    responses(
+        (status = 200, description = "Success", body = [HouseholdResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+    )
+)]
+pub async fn list_households(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(StatusCode, Json<Vec<HouseholdResponse>>), APIError> {
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let households = find_households_for_user(&mut conn, current_user.id).await?;
+
+    let response = households
+        .into_iter()
+        .map(HouseholdResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a household and its members
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = HouseholdDetailResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn get_household(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<HouseholdDetailResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let household = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let members = find_household_members(&mut conn, household_id).await?;
+
+    let response = HouseholdDetailResponse {
+        household: household.into(),
+        members: members
+            .into_iter()
+            .map(HouseholdMemberResponse::from)
+            .collect(),
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Update a household
+///
+/// Only owners and admins can update a household.
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateHouseholdRequest
+    ),
+    responses(
+        (status = 200, description = "Updated household successfully", body = HouseholdResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not an owner or admin", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn update_household(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<UpdateHouseholdRequest>,
+) -> Result<(StatusCode, Json<HouseholdResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (household, membership) =
+        find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    require_manager(&membership)?;
+
+    let household = match payload.name {
+        Some(name) => {
+            save_household(
+                &mut conn,
+                household_id,
+                HouseholdChangeset { name: Some(name) },
+            )
+            .await?
+        }
+        None => household,
+    };
+
+    Ok((StatusCode::OK, Json((household, membership).into())))
+}
+
+/// Leave a household
+///
+/// The last owner of a household cannot leave it, and should delete it instead.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/leave",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 204, description = "Left household successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is the last owner", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn leave_household(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    info!(user_id = %current_user.id, household_id = %household_id, "leaving household");
+    remove_member(&mut conn, household_id, current_user.id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Change a member's role
+///
+/// Only owners can change roles. A household always keeps at least one owner, so the last owner can't be demoted.
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/members/{user_id}",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("user_id" = String, Path, description = "The id of the member"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateMemberRequest
+    ),
+    responses(
+        (status = 200, description = "Changed role successfully", body = HouseholdMemberResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not an owner, or the member is the last owner", body = APIError),
+        (status = 404, description = "Household or member not found", body = APIError),
+    )
+)]
+pub async fn update_member(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, user_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateMemberRequest>,
+) -> Result<(StatusCode, Json<HouseholdMemberResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let user_id = parse_friendly_id(&user_id, ItemIdType::User)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    if membership.role != HouseholdRole::Owner {
+        return Err(APIErrorBuilder::new(Forbidden)
+            .detail("Only owners can change the roles of members.")
+            .build());
+    }
+
+    info!(user_id = %current_user.id, household_id = %household_id, member_id = %user_id, role = ?payload.role, "changing member role");
+    let member = update_member_role(&mut conn, household_id, user_id, payload.role).await?;
+    let user = find_user_by_id(&mut conn, &user_id).await?;
+
+    Ok((StatusCode::OK, Json((member, user).into())))
+}
+
+/// Delete a household
+///
+/// Only owners can delete a household.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}",
+    tag = "households",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 204, description = "Deleted household successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not an owner", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn delete_household(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    if membership.role != HouseholdRole::Owner {
+        return Err(APIErrorBuilder::new(Forbidden)
+            .detail("Only owners can delete a household.")
+            .build());
+    }
+
+    info!(user_id = %current_user.id, household_id = %household_id, "deleting household");
+    remove_household(&mut conn, household_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
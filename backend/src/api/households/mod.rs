@@ -0,0 +1,26 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{get, patch, post};
+use axum::{middleware, Router};
+use controllers::{
+    create_household, delete_household, get_household, leave_household, list_households,
+    update_household, update_member,
+};
+
+pub mod controllers;
+pub mod models;
+pub(crate) mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_household).get(list_households))
+        .route(
+            "/:household_id",
+            get(get_household)
+                .patch(update_household)
+                .delete(delete_household),
+        )
+        .route("/:household_id/leave", post(leave_household))
+        .route("/:household_id/members/:user_id", patch(update_member))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
@@ -0,0 +1,75 @@
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::household::{Household, HouseholdMember, HouseholdRole};
+use crate::db::user::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateHouseholdRequest {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "42 Wallaby Way", min_length = 1, max_length = 64)]
+    pub name: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateHouseholdRequest {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "42 Wallaby Way", min_length = 1, max_length = 64)]
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateMemberRequest {
+    pub role: HouseholdRole,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HouseholdResponse {
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    pub name: String,
+    /// The role of the current user in this household.
+    pub role: HouseholdRole,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<(Household, HouseholdMember)> for HouseholdResponse {
+    fn from((household, membership): (Household, HouseholdMember)) -> Self {
+        Self {
+            id: household.id.to_friendly_id(ItemIdType::Household),
+            name: household.name,
+            role: membership.role,
+            created_at: household.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HouseholdDetailResponse {
+    #[serde(flatten)]
+    pub household: HouseholdResponse,
+    pub members: Vec<HouseholdMemberResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HouseholdMemberResponse {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub role: HouseholdRole,
+    pub joined_at: chrono::NaiveDateTime,
+}
+
+impl From<(HouseholdMember, User)> for HouseholdMemberResponse {
+    fn from((membership, user): (HouseholdMember, User)) -> Self {
+        Self {
+            id: user.id.to_friendly_id(ItemIdType::User),
+            first_name: user.first_name,
+            last_name: user.last_name,
+            role: membership.role,
+            joined_at: membership.joined_at,
+        }
+    }
+}
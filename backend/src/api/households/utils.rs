@@ -0,0 +1,231 @@
+use crate::api::error::ErrorType::{Forbidden, HouseholdNotFound, NotFound};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::field_error;
+use crate::db::database::Connection;
+use crate::db::household::{
+    Household, HouseholdChangeset, HouseholdMember, HouseholdRole, NewHousehold, NewHouseholdMember,
+};
+use crate::db::schema::{household_members, households, users};
+use crate::db::user::User;
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+/// Creates a household and makes the given user its owner.
+pub async fn create_household(
+    conn: &mut Connection,
+    household: NewHousehold,
+    owner_id: Uuid,
+) -> Result<(Household, HouseholdMember), APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let household = diesel::insert_into(households::table)
+                .values(&household)
+                .returning(Household::as_returning())
+                .get_result(conn)
+                .await?;
+
+            let membership = diesel::insert_into(household_members::table)
+                .values(&NewHouseholdMember {
+                    household_id: household.id,
+                    user_id: owner_id,
+                    role: HouseholdRole::Owner,
+                })
+                .returning(HouseholdMember::as_returning())
+                .get_result(conn)
+                .await?;
+
+            Ok((household, membership))
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+pub async fn find_households_for_user(
+    conn: &mut Connection,
+    user_id: Uuid,
+) -> Result<Vec<(Household, HouseholdMember)>, APIError> {
+    Household::for_user(user_id)
+        .order(households::created_at.asc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds a household along with the given user's membership of it.
+///
+/// Households the user is not a member of are reported as not found, so that their existence is not leaked.
+pub async fn find_household_for_user(
+    conn: &mut Connection,
+    household_id: Uuid,
+    user_id: Uuid,
+) -> Result<(Household, HouseholdMember), APIError> {
+    Household::for_user(user_id)
+        .filter(households::id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(HouseholdNotFound).build())
+}
+
+/// Ensures the given membership is allowed to manage the household.
+pub fn require_manager(membership: &HouseholdMember) -> Result<(), APIError> {
+    if membership.role.can_manage() {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(Forbidden)
+        .detail("Only owners and admins can do this.")
+        .build())
+}
+
+pub async fn find_household_members(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<(HouseholdMember, User)>, APIError> {
+    household_members::table
+        .inner_join(users::table)
+        .filter(household_members::household_id.eq(household_id))
+        .order(household_members::joined_at.asc())
+        .select((HouseholdMember::as_select(), User::as_select()))
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// The ids of every member of the household, in the order they joined.
+pub async fn find_household_member_ids(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Uuid>, APIError> {
+    household_members::table
+        .filter(household_members::household_id.eq(household_id))
+        .order((
+            household_members::joined_at.asc(),
+            household_members::user_id.asc(),
+        ))
+        .select(household_members::user_id)
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Parses a user id supplied by the client for `field`, ensuring they are one of the given members.
+pub fn parse_member_id(
+    id: &str,
+    member_ids: &[Uuid],
+    field: &'static str,
+) -> Result<Uuid, APIError> {
+    let user_id = parse_friendly_id(id, ItemIdType::User)?;
+
+    if !member_ids.contains(&user_id) {
+        return Err(field_error(
+            field,
+            "household_member",
+            "The user must be a member of the household.",
+        ));
+    }
+
+    Ok(user_id)
+}
+
+pub async fn update_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+    changes: HouseholdChangeset,
+) -> Result<Household, APIError> {
+    diesel::update(households::table.find(household_id))
+        .set(&changes)
+        .returning(Household::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Locks the owner memberships of a household until the end of the transaction, and returns their user ids.
+///
+/// Taking the lock before removing or demoting an owner means two owners doing so at the same time can't leave the
+/// household without one.
+async fn lock_owner_ids(conn: &mut Connection, household_id: Uuid) -> QueryResult<Vec<Uuid>> {
+    household_members::table
+        .filter(household_members::household_id.eq(household_id))
+        .filter(household_members::role.eq(HouseholdRole::Owner))
+        .select(household_members::user_id)
+        .for_update()
+        .load(conn)
+        .await
+}
+
+/// Removes a user from a household, unless they are its last owner.
+pub async fn remove_member(
+    conn: &mut Connection,
+    household_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    conn.transaction::<_, APIError, _>(|conn| {
+        async move {
+            if lock_owner_ids(conn, household_id).await? == [user_id] {
+                return Err(APIErrorBuilder::new(Forbidden)
+                    .detail(
+                        "You are the last owner of this household. Delete the household instead.",
+                    )
+                    .build());
+            }
+
+            diesel::delete(household_members::table.find((household_id, user_id)))
+                .execute(conn)
+                .await?;
+
+            Ok(())
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+/// Changes the role of a member of a household, unless that would leave the household without an owner.
+pub async fn update_member_role(
+    conn: &mut Connection,
+    household_id: Uuid,
+    user_id: Uuid,
+    role: HouseholdRole,
+) -> Result<HouseholdMember, APIError> {
+    conn.transaction::<_, APIError, _>(|conn| {
+        async move {
+            let owner_ids = lock_owner_ids(conn, household_id).await?;
+            if role != HouseholdRole::Owner && owner_ids == [user_id] {
+                return Err(APIErrorBuilder::new(Forbidden)
+                    .detail("A household needs an owner. Make another member an owner first.")
+                    .build());
+            }
+
+            diesel::update(household_members::table.find((household_id, user_id)))
+                .set(household_members::role.eq(role))
+                .returning(HouseholdMember::as_returning())
+                .get_result(conn)
+                .await
+                .optional()?
+                .ok_or_else(|| {
+                    APIErrorBuilder::new(NotFound)
+                        .detail("The user is not a member of this household.")
+                        .build()
+                })
+        }
+        .scope_boxed()
+    })
+    .await
+}
+
+pub async fn delete_household(conn: &mut Connection, household_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(households::table.find(household_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
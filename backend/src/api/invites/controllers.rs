@@ -0,0 +1,206 @@
+use super::models::{CreateInviteRequest, InviteResponse, RedeemInviteRequest};
+use super::utils::{
+    create_invite as insert_invite, find_invites_for_household, generate_invite_code,
+    generate_invite_token, redeem_invite as consume_invite, revoke_invite as mark_invite_revoked,
+};
+use crate::api::auth::utils::require_verified_email;
+use crate::api::error::APIError;
+use crate::api::households::models::HouseholdResponse;
+use crate::api::households::utils::{find_household_for_user, require_manager};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::secret::hash_secret;
+use crate::api::utils::validation::ValidatedJson;
+use crate::config::VerifiedAction;
+use crate::db::invite::NewHouseholdInvite;
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use tracing::info;
+
+/// Create an invite to a household
+///
+/// Only owners and admins can create invites.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/invites",
+    tag = "invites",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateInviteRequest
+    ),
+    responses(
+        (status = 201, description = "Created new invite successfully", body = InviteResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not an owner or admin", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_invite(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateInviteRequest>,
+) -> Result<(StatusCode, Json<InviteResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    require_verified_email(
+        &current_user,
+        &state.settings.verification,
+        VerifiedAction::InviteMembers,
+    )?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    require_manager(&membership)?;
+
+    info!(user_id = %current_user.id, household_id = %household_id, "creating household invite");
+
+    let expires_at =
+        chrono::Utc::now().naive_utc() + chrono::Duration::hours(payload.expires_in_hours.into());
+    let token = generate_invite_token();
+    let invite = insert_invite(
+        &mut conn,
+        NewHouseholdInvite {
+            household_id,
+            created_by: current_user.id,
+            code: generate_invite_code(),
+            token_hash: hash_secret(&token),
+            max_uses: payload.max_uses,
+            expires_at,
+        },
+    )
+    .await?;
+
+    let response = InviteResponse {
+        token: Some(token),
+        ..invite.into()
+    };
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// List the invites of a household
+///
+/// Only owners and admins can list invites.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/invites",
+    tag = "invites",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [InviteResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not an owner or admin", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_invites(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<InviteResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    require_manager(&membership)?;
+
+    let invites = find_invites_for_household(&mut conn, household_id).await?;
+    let response = invites.into_iter().map(InviteResponse::from).collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Revoke an invite
+///
+/// Revoked invites can no longer be redeemed. Only owners and admins can revoke invites.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/invites/{invite_id}",
+    tag = "invites",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("invite_id" = String, Path, description = "The invite id"),
+    ),
+    responses(
+        (status = 200, description = "Revoked invite successfully", body = InviteResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not an owner or admin", body = APIError),
+        (status = 404, description = "Household or invite not found", body = APIError),
+    )
+)]
+pub async fn revoke_invite(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, invite_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<InviteResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let invite_id = parse_friendly_id(&invite_id, ItemIdType::Invite)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    require_manager(&membership)?;
+
+    info!(user_id = %current_user.id, invite_id = %invite_id, "revoking household invite");
+    let invite = mark_invite_revoked(&mut conn, household_id, invite_id).await?;
+
+    Ok((StatusCode::OK, Json(invite.into())))
+}
+
+/// Redeem an invite
+///
+/// Joins the household the invite belongs to as a member.
+#[utoipa::path(
+    post,
+    path = "/invites/redeem",
+    tag = "invites",
+    security(
+        ("api_token" = [])
+    ),
+    request_body(
+        content_type = "application/json",
+        content = RedeemInviteRequest
+    ),
+    responses(
+        (status = 200, description = "Joined household successfully", body = HouseholdResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Invite not found", body = APIError),
+        (status = 409, description = "User is already a member of the household", body = APIError),
+        (status = 410, description = "Invite expired, revoked or already used", body = APIError),
+    )
+)]
+pub async fn redeem_invite(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    ValidatedJson(payload): ValidatedJson<RedeemInviteRequest>,
+) -> Result<(StatusCode, Json<HouseholdResponse>), APIError> {
+    require_verified_email(
+        &current_user,
+        &state.settings.verification,
+        VerifiedAction::JoinHousehold,
+    )?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+
+    let household_id = consume_invite(&mut conn, &payload.code, current_user.id).await?;
+    let household = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    Ok((StatusCode::OK, Json(household.into())))
+}
@@ -0,0 +1,23 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{delete, post};
+use axum::{middleware, Router};
+use controllers::{create_invite, list_invites, redeem_invite, revoke_invite};
+
+pub mod controllers;
+pub mod models;
+mod utils;
+
+/// Routes for managing the invites of a single household.
+pub fn get_household_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", post(create_invite).get(list_invites))
+        .route("/:invite_id", delete(revoke_invite))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/redeem", post(redeem_invite))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
@@ -0,0 +1,81 @@
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::invite::HouseholdInvite;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+use validator::Validate;
+
+const DEFAULT_EXPIRY_HOURS: u32 = 72;
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateInviteRequest {
+    /// How many times the invite can be redeemed. Omit for an invite with unlimited uses.
+    #[validate(range(min = 1))]
+    #[schema(example = 1, minimum = 1)]
+    pub max_uses: Option<i32>,
+
+    /// How long the invite is valid for, in hours.
+    #[validate(range(min = 1, max = 720))]
+    #[serde(default = "default_expiry_hours")]
+    #[schema(example = 72, minimum = 1, maximum = 720, default = 72)]
+    pub expires_in_hours: u32,
+}
+
+fn default_expiry_hours() -> u32 {
+    DEFAULT_EXPIRY_HOURS
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct RedeemInviteRequest {
+    /// Either the short invite code or the link token.
+    #[validate(length(min = 1))]
+    #[schema(example = "K7QM2XRP")]
+    pub code: String,
+}
+
+impl fmt::Debug for RedeemInviteRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedeemInviteRequest")
+            .field("code", &"********")
+            .finish()
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct InviteResponse {
+    #[schema(example = "invite|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: String,
+    /// A short code that can be typed in by hand.
+    #[schema(example = "K7QM2XRP")]
+    pub code: String,
+    /// A long token suitable for embedding in a link.
+    ///
+    /// Only returned when the invite is created, since it isn't stored.
+    pub token: Option<String>,
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub expires_at: chrono::NaiveDateTime,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<HouseholdInvite> for InviteResponse {
+    fn from(invite: HouseholdInvite) -> Self {
+        Self {
+            id: invite.id.to_friendly_id(ItemIdType::Invite),
+            household_id: invite.household_id.to_friendly_id(ItemIdType::Household),
+            created_by: invite.created_by.to_friendly_id(ItemIdType::User),
+            code: invite.code,
+            token: None,
+            max_uses: invite.max_uses,
+            use_count: invite.use_count,
+            expires_at: invite.expires_at,
+            revoked_at: invite.revoked_at,
+            created_at: invite.created_at,
+        }
+    }
+}
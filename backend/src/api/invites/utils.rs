@@ -0,0 +1,196 @@
+use crate::api::error::ErrorType::{
+    AlreadyHouseholdMember, InviteAlreadyUsed, InviteExpired, InviteNotFound, InviteRevoked,
+};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::utils::secret::hash_secret;
+use crate::db::database::Connection;
+use crate::db::household::{HouseholdRole, NewHouseholdMember};
+use crate::db::invite::{HouseholdInvite, NewHouseholdInvite};
+use crate::db::schema::{household_invites, household_members};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use diesel::dsl::{exists, now};
+use diesel::prelude::*;
+use diesel::select;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::info;
+use uuid::Uuid;
+
+/// Characters used in short invite codes. Ambiguous characters (0/O, 1/I) are left out.
+///
+/// Only upper case letters are used, which is the form [`normalize_invite_code`] brings user input into.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 8;
+
+pub fn generate_invite_code() -> String {
+    let mut bytes = [0u8; CODE_LENGTH];
+    OsRng.fill_bytes(&mut bytes);
+
+    bytes
+        .iter()
+        .map(|b| CODE_ALPHABET[*b as usize % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// Normalizes a short invite code as typed by a user into the form it is stored in.
+pub fn normalize_invite_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+pub fn generate_invite_token() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+
+    base62::encode(u128::from_le_bytes(bytes))
+}
+
+pub async fn create_invite(
+    conn: &mut Connection,
+    invite: NewHouseholdInvite,
+) -> Result<HouseholdInvite, APIError> {
+    diesel::insert_into(household_invites::table)
+        .values(&invite)
+        .returning(HouseholdInvite::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn find_invites_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<HouseholdInvite>, APIError> {
+    HouseholdInvite::all()
+        .filter(household_invites::household_id.eq(household_id))
+        .order(household_invites::created_at.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Revokes an invite. Revoking an invite that was already revoked leaves it as it was.
+pub async fn revoke_invite(
+    conn: &mut Connection,
+    household_id: Uuid,
+    invite_id: Uuid,
+) -> Result<HouseholdInvite, APIError> {
+    let revoked = diesel::update(
+        household_invites::table
+            .filter(household_invites::id.eq(invite_id))
+            .filter(household_invites::household_id.eq(household_id))
+            .filter(household_invites::revoked_at.is_null()),
+    )
+    .set(household_invites::revoked_at.eq(now.nullable()))
+    .returning(HouseholdInvite::as_returning())
+    .get_result(conn)
+    .await
+    .optional()
+    .map_err(APIError::from)?;
+    if let Some(invite) = revoked {
+        return Ok(invite);
+    }
+
+    HouseholdInvite::all()
+        .filter(household_invites::id.eq(invite_id))
+        .filter(household_invites::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(InviteNotFound).build())
+}
+
+/// Redeems an invite for the given user, adding them to the household as a member.
+///
+/// Returns the id of the household that was joined.
+pub async fn redeem_invite(
+    conn: &mut Connection,
+    code: &str,
+    user_id: Uuid,
+) -> Result<Uuid, APIError> {
+    // Short codes are matched case-insensitively, link tokens are not.
+    let token = code.trim();
+    let code = normalize_invite_code(code);
+    let invite = HouseholdInvite::by_code_or_token_hash(&code, hash_secret(token))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(InviteNotFound).build())?;
+
+    let already_member = select(exists(
+        household_members::table.find((invite.household_id, user_id)),
+    ))
+    .get_result::<bool>(conn)
+    .await
+    .map_err(APIError::from)?;
+
+    if already_member {
+        return Err(APIErrorBuilder::new(AlreadyHouseholdMember).build());
+    }
+
+    check_invite_is_redeemable(&invite)?;
+
+    let invite_id = invite.id;
+    let household_id = invite.household_id;
+    let redeemed = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            async move {
+                // The usage check is repeated in the update so that concurrent redemptions cannot exceed the limit.
+                let claimed = diesel::update(
+                    household_invites::table
+                        .filter(household_invites::id.eq(invite_id))
+                        .filter(household_invites::revoked_at.is_null())
+                        .filter(household_invites::expires_at.gt(now))
+                        .filter(household_invites::max_uses.is_null().or(
+                            household_invites::max_uses.gt(household_invites::use_count.nullable()),
+                        )),
+                )
+                .set(household_invites::use_count.eq(household_invites::use_count + 1))
+                .execute(conn)
+                .await?;
+
+                if claimed == 0 {
+                    return Ok(false);
+                }
+
+                diesel::insert_into(household_members::table)
+                    .values(&NewHouseholdMember {
+                        household_id,
+                        user_id,
+                        role: HouseholdRole::Member,
+                    })
+                    .execute(conn)
+                    .await?;
+
+                Ok(true)
+            }
+            .scope_boxed()
+        })
+        .await
+        .map_err(APIError::from)?;
+
+    if !redeemed {
+        return Err(APIErrorBuilder::new(InviteAlreadyUsed).build());
+    }
+
+    info!(user_id = %user_id, household_id = %household_id, "redeemed household invite");
+
+    Ok(household_id)
+}
+
+fn check_invite_is_redeemable(invite: &HouseholdInvite) -> Result<(), APIError> {
+    if invite.is_revoked() {
+        return Err(APIErrorBuilder::new(InviteRevoked).build());
+    }
+
+    if invite.is_expired() {
+        return Err(APIErrorBuilder::new(InviteExpired).build());
+    }
+
+    if invite.is_used_up() {
+        return Err(APIErrorBuilder::new(InviteAlreadyUsed).build());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use super::models::{
+    CreateSettlementRequest, CurrencyLedgerResponse, LedgerResponse, MemberBalanceResponse,
+    SettlementResponse, TransferResponse,
+};
+use super::simplify::{net_balances, simplify_debts};
+use super::utils::{
+    create_settlement as insert_settlement, delete_settlement as remove_settlement,
+    find_ledger_entries, find_settlement, find_settlements_for_household,
+    require_settlement_editor,
+};
+use crate::api::error::ErrorType::Unknown;
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::households::utils::{
+    find_household_for_user, find_household_member_ids, parse_member_id,
+};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType, ToFriendlyId};
+use crate::api::utils::validation::{field_error, ValidatedJson};
+use crate::db::settlement::NewSettlement;
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use tracing::info;
+
+/// Get the balances of a household
+///
+/// Returns what each member is owed or owes, along with the fewest transfers that would settle up.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/ledger",
+    tag = "ledger",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = LedgerResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn get_ledger(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<LedgerResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let entries = find_ledger_entries(&mut conn, household_id).await?;
+
+    let currencies = net_balances(entries)
+        .ok_or_else(|| {
+            APIErrorBuilder::new(Unknown)
+                .cause("ledger balance overflowed")
+                .build()
+        })?
+        .into_iter()
+        .map(|(currency, mut balances)| {
+            // Current members are listed even if they aren't part of any bills yet.
+            for member_id in &member_ids {
+                balances.entry(*member_id).or_default();
+            }
+
+            let transfers = simplify_debts(&balances);
+            CurrencyLedgerResponse {
+                currency,
+                balances: balances
+                    .into_iter()
+                    .map(|(user_id, balance)| MemberBalanceResponse {
+                        member_id: user_id.to_friendly_id(ItemIdType::User),
+                        balance,
+                    })
+                    .collect(),
+                transfers: transfers.into_iter().map(TransferResponse::from).collect(),
+            }
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(LedgerResponse { currencies })))
+}
+
+/// Record a settlement
+///
+/// Records money paid from one member to another, which counts towards their balances.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/ledger/settlements",
+    tag = "ledger",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateSettlementRequest
+    ),
+    responses(
+        (status = 201, description = "Recorded settlement successfully", body = SettlementResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_settlement(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateSettlementRequest>,
+) -> Result<(StatusCode, Json<SettlementResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+    let from_user_id = match payload.from_member_id {
+        Some(ref from_member_id) => parse_member_id(from_member_id, &member_ids, "from_member_id")?,
+        None => current_user.id,
+    };
+    let to_user_id = parse_member_id(&payload.to_member_id, &member_ids, "to_member_id")?;
+    if from_user_id == to_user_id {
+        return Err(field_error(
+            "to_member_id",
+            "different_member",
+            "A member cannot settle up with themselves.",
+        ));
+    }
+
+    info!(user_id = %current_user.id, household_id = %household_id, "recording settlement");
+    let settlement = insert_settlement(
+        &mut conn,
+        NewSettlement {
+            household_id,
+            created_by: Some(current_user.id),
+            from_user_id,
+            to_user_id,
+            amount: payload.amount,
+            currency: payload.currency,
+            note: payload.note,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(settlement.into())))
+}
+
+/// List the settlements of a household
+///
+/// Settlements are returned newest first.
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/ledger/settlements",
+    tag = "ledger",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [SettlementResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_settlements(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<SettlementResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let settlements = find_settlements_for_household(&mut conn, household_id).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(
+            settlements
+                .into_iter()
+                .map(SettlementResponse::from)
+                .collect(),
+        ),
+    ))
+}
+
+/// Remove a settlement
+///
+/// Only owners, admins, the members involved and the creator of a settlement can remove it.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/ledger/settlements/{settlement_id}",
+    tag = "ledger",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("settlement_id" = String, Path, description = "The settlement id"),
+    ),
+    responses(
+        (status = 204, description = "Removed settlement successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 403, description = "User is not allowed to remove the settlement", body = APIError),
+        (status = 404, description = "Household or settlement not found", body = APIError),
+    )
+)]
+pub async fn delete_settlement(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, settlement_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let settlement_id = parse_friendly_id(&settlement_id, ItemIdType::Settlement)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    let (_, membership) = find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let settlement = find_settlement(&mut conn, household_id, settlement_id).await?;
+    require_settlement_editor(&settlement, &membership, current_user.id)?;
+
+    info!(user_id = %current_user.id, settlement_id = %settlement_id, "removing settlement");
+    remove_settlement(&mut conn, settlement_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,21 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{delete, get};
+use axum::{middleware, Router};
+use controllers::{create_settlement, delete_settlement, get_ledger, list_settlements};
+
+pub mod controllers;
+pub mod models;
+mod simplify;
+mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_ledger))
+        .route(
+            "/settlements",
+            get(list_settlements).post(create_settlement),
+        )
+        .route("/settlements/:settlement_id", delete(delete_settlement))
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
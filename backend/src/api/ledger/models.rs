@@ -0,0 +1,116 @@
+use super::simplify::Transfer;
+use crate::api::bills::currency::validate_currency;
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::settlement::Settlement;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Serialize, ToSchema)]
+pub struct LedgerResponse {
+    /// Balances are kept separately for each currency bills have been paid in.
+    pub currencies: Vec<CurrencyLedgerResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CurrencyLedgerResponse {
+    #[schema(example = "NZD")]
+    pub currency: String,
+    pub balances: Vec<MemberBalanceResponse>,
+    /// The fewest transfers that would settle every balance.
+    pub transfers: Vec<TransferResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MemberBalanceResponse {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub member_id: String,
+    /// Positive if the member is owed money, negative if they owe money.
+    #[schema(example = -6_173)]
+    pub balance: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TransferResponse {
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub from_member_id: String,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub to_member_id: String,
+    #[schema(example = 6_173)]
+    pub amount: i64,
+}
+
+impl From<Transfer> for TransferResponse {
+    fn from(transfer: Transfer) -> Self {
+        Self {
+            from_member_id: transfer.from.to_friendly_id(ItemIdType::User),
+            to_member_id: transfer.to.to_friendly_id(ItemIdType::User),
+            amount: transfer.amount,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateSettlementRequest {
+    /// The member who paid. Defaults to the current user.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub from_member_id: Option<String>,
+
+    /// The member who was paid.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub to_member_id: String,
+
+    /// The amount paid, in the minor unit of the currency (e.g. cents).
+    #[validate(range(min = 1))]
+    #[schema(example = 6_173, minimum = 1)]
+    pub amount: i64,
+
+    /// An ISO 4217 currency code.
+    #[validate(custom = "validate_currency")]
+    #[schema(example = "NZD", min_length = 3, max_length = 3)]
+    pub currency: String,
+
+    #[validate(length(max = 256))]
+    #[schema(example = "Bank transfer", max_length = 256)]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SettlementResponse {
+    #[schema(example = "settlement|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub from_member_id: String,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub to_member_id: String,
+    #[schema(example = 6_173)]
+    pub amount: i64,
+    #[schema(example = "NZD")]
+    pub currency: String,
+    pub note: Option<String>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<Settlement> for SettlementResponse {
+    fn from(settlement: Settlement) -> Self {
+        Self {
+            id: settlement.id.to_friendly_id(ItemIdType::Settlement),
+            household_id: settlement
+                .household_id
+                .to_friendly_id(ItemIdType::Household),
+            from_member_id: settlement.from_user_id.to_friendly_id(ItemIdType::User),
+            to_member_id: settlement.to_user_id.to_friendly_id(ItemIdType::User),
+            amount: settlement.amount,
+            currency: settlement.currency,
+            note: settlement.note,
+            created_by: settlement
+                .created_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            created_at: settlement.created_at,
+        }
+    }
+}
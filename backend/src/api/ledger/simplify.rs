@@ -0,0 +1,333 @@
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Past this many members with an outstanding balance, finding the smallest set of transfers
+/// gets too expensive and we fall back to settling everyone as a single group.
+const MAX_EXACT_MEMBERS: usize = 16;
+
+/// A payment from one member to another that settles part of what they owe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub amount: i64,
+}
+
+/// Adds up ledger entries into the net balance of each member, per currency.
+///
+/// A positive balance means the member is owed money, a negative balance means they owe money.
+/// Returns `None` if a balance grows too large to represent.
+pub fn net_balances<I>(entries: I) -> Option<BTreeMap<String, BTreeMap<Uuid, i64>>>
+where
+    I: IntoIterator<Item = (String, Uuid, i64)>,
+{
+    let mut balances: BTreeMap<String, BTreeMap<Uuid, i64>> = BTreeMap::new();
+    for (currency, user_id, amount) in entries {
+        let balance = balances
+            .entry(currency)
+            .or_default()
+            .entry(user_id)
+            .or_default();
+        *balance = balance.checked_add(amount)?;
+    }
+
+    Some(balances)
+}
+
+/// Finds the fewest transfers that bring every balance back to zero.
+///
+/// The members are split into as many groups that add up to zero as possible, since a group of
+/// `n` members can always be settled with `n - 1` transfers and never with fewer. Each group is
+/// then settled by repeatedly having the member who owes the most pay the member who is owed the
+/// most. Ties are broken by member id so the same balances always give the same transfers.
+pub fn simplify_debts(balances: &BTreeMap<Uuid, i64>) -> Vec<Transfer> {
+    let outstanding: Vec<(Uuid, i64)> = balances
+        .iter()
+        .filter(|(_, &balance)| balance != 0)
+        .map(|(&user_id, &balance)| (user_id, balance))
+        .collect();
+
+    if outstanding.len() > MAX_EXACT_MEMBERS {
+        return settle_group(&outstanding);
+    }
+
+    let amounts: Vec<i64> = outstanding.iter().map(|(_, balance)| *balance).collect();
+    zero_sum_groups(&amounts)
+        .into_iter()
+        .flat_map(|group| {
+            let members: Vec<(Uuid, i64)> = group.into_iter().map(|i| outstanding[i]).collect();
+            settle_group(&members)
+        })
+        .collect()
+}
+
+/// Partitions the balances into the largest number of groups that each add up to zero.
+///
+/// `counts[mask]` is the most zero-sum groups the members in `mask` can be split into. Removing
+/// members one at a time, every subset along the way that adds up to zero closes off a group.
+fn zero_sum_groups(amounts: &[i64]) -> Vec<Vec<usize>> {
+    let n = amounts.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // Subsets of balances that each fit in an i64 can still add up to more than one.
+    let full = (1usize << n) - 1;
+    let mut sums = vec![0i128; full + 1];
+    let mut counts = vec![0u32; full + 1];
+    for mask in 1..=full {
+        let lowest = mask.trailing_zeros() as usize;
+        sums[mask] = sums[mask & (mask - 1)] + i128::from(amounts[lowest]);
+
+        let best = members_of(mask, n)
+            .map(|i| counts[mask ^ (1 << i)])
+            .max()
+            .unwrap_or_default();
+        counts[mask] = best + u32::from(sums[mask] == 0);
+    }
+
+    let mut groups = Vec::new();
+    let mut start = full;
+    let mut mask = full;
+    while mask != 0 {
+        if mask != start && sums[mask] == 0 {
+            groups.push(start ^ mask);
+            start = mask;
+        }
+
+        let closes_group = u32::from(sums[mask] == 0);
+        let removed = members_of(mask, n)
+            .find(|&i| counts[mask ^ (1 << i)] + closes_group == counts[mask])
+            .expect("every non-empty subset has a member to remove");
+        mask ^= 1 << removed;
+    }
+    groups.push(start);
+
+    groups
+        .into_iter()
+        .map(|group| members_of(group, n).collect())
+        .collect()
+}
+
+fn members_of(mask: usize, n: usize) -> impl Iterator<Item = usize> {
+    (0..n).filter(move |i| mask & (1 << i) != 0)
+}
+
+/// Settles a group by matching the largest debtor with the largest creditor until one side runs
+/// out. Within a group that can't be split any further this takes `n - 1` transfers.
+///
+/// Debts are kept as i128 since the most a member can owe (`-i64::MIN`) doesn't fit in an i64.
+fn settle_group(members: &[(Uuid, i64)]) -> Vec<Transfer> {
+    let mut debtors: Vec<(Uuid, i128)> = members
+        .iter()
+        .filter(|(_, balance)| *balance < 0)
+        .map(|&(user_id, balance)| (user_id, -i128::from(balance)))
+        .collect();
+    let mut creditors: Vec<(Uuid, i128)> = members
+        .iter()
+        .filter(|(_, balance)| *balance > 0)
+        .map(|&(user_id, balance)| (user_id, i128::from(balance)))
+        .collect();
+
+    let mut transfers = Vec::new();
+    while let (Some(d), Some(c)) = (largest(&debtors), largest(&creditors)) {
+        let amount = debtors[d].1.min(creditors[c].1);
+        transfers.push(Transfer {
+            from: debtors[d].0,
+            to: creditors[c].0,
+            amount: i64::try_from(amount).expect("a transfer is never more than a credit"),
+        });
+
+        debtors[d].1 -= amount;
+        creditors[c].1 -= amount;
+        debtors.retain(|(_, balance)| *balance > 0);
+        creditors.retain(|(_, balance)| *balance > 0);
+    }
+
+    transfers
+}
+
+/// The index of the largest balance, preferring the lowest member id on a tie.
+fn largest(balances: &[(Uuid, i128)]) -> Option<usize> {
+    balances
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn balances(amounts: &[i64]) -> BTreeMap<Uuid, i64> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, &amount)| (user(i as u128 + 1), amount))
+            .collect()
+    }
+
+    fn apply(balances: &BTreeMap<Uuid, i64>, transfers: &[Transfer]) -> BTreeMap<Uuid, i128> {
+        let mut balances: BTreeMap<Uuid, i128> = balances
+            .iter()
+            .map(|(&user_id, &balance)| (user_id, i128::from(balance)))
+            .collect();
+        for transfer in transfers {
+            *balances.get_mut(&transfer.from).unwrap() += i128::from(transfer.amount);
+            *balances.get_mut(&transfer.to).unwrap() -= i128::from(transfer.amount);
+        }
+        balances
+    }
+
+    fn assert_settles(balances: &BTreeMap<Uuid, i64>, transfers: &[Transfer]) {
+        assert!(transfers.iter().all(|transfer| transfer.amount > 0));
+        assert!(apply(balances, transfers).values().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn net_balances_adds_up_entries_per_currency() {
+        let entries = vec![
+            ("NZD".to_string(), user(1), 1000),
+            ("NZD".to_string(), user(1), -500),
+            ("NZD".to_string(), user(2), -500),
+            ("AUD".to_string(), user(2), 300),
+            ("AUD".to_string(), user(1), -300),
+        ];
+
+        let result = net_balances(entries).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result["NZD"][&user(1)], 500);
+        assert_eq!(result["NZD"][&user(2)], -500);
+        assert_eq!(result["AUD"][&user(1)], -300);
+        assert_eq!(result["AUD"][&user(2)], 300);
+    }
+
+    #[test]
+    fn net_balances_rejects_balances_that_overflow() {
+        let entries = vec![
+            ("NZD".to_string(), user(1), i64::MAX),
+            ("NZD".to_string(), user(1), 1),
+        ];
+
+        assert!(net_balances(entries).is_none());
+    }
+
+    #[test]
+    fn huge_balances_do_not_overflow() {
+        let balances = balances(&[i64::MIN, i64::MAX, 1, i64::MAX, -i64::MAX]);
+
+        let transfers = simplify_debts(&balances);
+
+        assert_settles(&balances, &transfers);
+    }
+
+    #[test]
+    fn settled_balances_need_no_transfers() {
+        assert!(simplify_debts(&BTreeMap::new()).is_empty());
+        assert!(simplify_debts(&balances(&[0, 0, 0])).is_empty());
+    }
+
+    #[test]
+    fn one_debt_is_one_transfer() {
+        let transfers = simplify_debts(&balances(&[-250, 250]));
+
+        assert_eq!(
+            transfers,
+            vec![Transfer {
+                from: user(1),
+                to: user(2),
+                amount: 250,
+            }]
+        );
+    }
+
+    #[test]
+    fn chains_of_debt_collapse() {
+        // 1 owes 2, who owes 3 the same amount, so 1 can pay 3 directly.
+        let transfers = simplify_debts(&balances(&[-100, 0, 100]));
+
+        assert_eq!(
+            transfers,
+            vec![Transfer {
+                from: user(1),
+                to: user(3),
+                amount: 100,
+            }]
+        );
+    }
+
+    #[test]
+    fn splits_into_independent_groups() {
+        // Settling greedily across everyone takes five transfers, but {4, 6} can settle between
+        // themselves, leaving four members who need three.
+        let balances = balances(&[-8, 6, -2, 3, 4, -3]);
+
+        let transfers = simplify_debts(&balances);
+
+        assert_settles(&balances, &transfers);
+        assert_eq!(transfers.len(), 4);
+        assert!(transfers.contains(&Transfer {
+            from: user(6),
+            to: user(4),
+            amount: 3,
+        }));
+    }
+
+    #[test]
+    fn never_needs_more_than_one_less_than_the_members() {
+        let balances = balances(&[-700, -300, 150, 250, 600]);
+
+        let transfers = simplify_debts(&balances);
+
+        assert_settles(&balances, &transfers);
+        assert!(transfers.len() <= 4);
+    }
+
+    #[test]
+    fn is_deterministic() {
+        let balances = balances(&[-5, -5, 5, 5, -10, 10]);
+
+        let first = simplify_debts(&balances);
+        let second = simplify_debts(&balances);
+
+        assert_settles(&balances, &first);
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 3);
+        assert_eq!(
+            first[0],
+            Transfer {
+                from: user(1),
+                to: user(3),
+                amount: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn ties_prefer_the_lowest_member_id() {
+        let transfers = simplify_debts(&balances(&[-10, -10, 20]));
+
+        assert_eq!(transfers[0].from, user(1));
+        assert_eq!(transfers[1].from, user(2));
+    }
+
+    #[test]
+    fn falls_back_to_a_single_group_for_large_households() {
+        let amounts: Vec<i64> = (0..20)
+            .map(|i| if i % 2 == 0 { -(i + 1) } else { i })
+            .collect();
+        let balances = balances(&amounts);
+        assert_eq!(balances.values().sum::<i64>(), 0);
+
+        let transfers = simplify_debts(&balances);
+
+        assert_settles(&balances, &transfers);
+        assert!(transfers.len() < balances.len());
+    }
+}
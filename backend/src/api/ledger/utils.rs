@@ -0,0 +1,130 @@
+use crate::api::error::ErrorType::{Forbidden, SettlementNotFound};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::db::bill::BillStatus;
+use crate::db::database::Connection;
+use crate::db::household::HouseholdMember;
+use crate::db::schema::{bill_splits, bills, settlements};
+use crate::db::settlement::{NewSettlement, Settlement};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+/// Finds every change to a member's balance in a household, as `(currency, user id, amount)`.
+///
+/// Paying a bill or sending a settlement adds to the balance, owing part of a bill or receiving a
+/// settlement takes away from it. Draft bills don't count until they are posted.
+pub async fn find_ledger_entries(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<(String, Uuid, i64)>, APIError> {
+    let paid: Vec<(String, Uuid, i64)> = bills::table
+        .filter(bills::household_id.eq(household_id))
+        .filter(bills::status.eq(BillStatus::Posted))
+        .select((bills::currency, bills::payer_id, bills::amount))
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    let owed: Vec<(String, Uuid, i64)> = bill_splits::table
+        .inner_join(bills::table)
+        .filter(bills::household_id.eq(household_id))
+        .filter(bills::status.eq(BillStatus::Posted))
+        .select((bills::currency, bill_splits::user_id, bill_splits::amount))
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    let settled = find_settlements_for_household(conn, household_id).await?;
+
+    let entries = paid
+        .into_iter()
+        .chain(
+            owed.into_iter()
+                .map(|(currency, user_id, amount)| (currency, user_id, -amount)),
+        )
+        .chain(settled.into_iter().flat_map(|settlement| {
+            [
+                (
+                    settlement.currency.clone(),
+                    settlement.from_user_id,
+                    settlement.amount,
+                ),
+                (
+                    settlement.currency,
+                    settlement.to_user_id,
+                    -settlement.amount,
+                ),
+            ]
+        }))
+        .collect();
+
+    Ok(entries)
+}
+
+pub async fn create_settlement(
+    conn: &mut Connection,
+    settlement: NewSettlement,
+) -> Result<Settlement, APIError> {
+    diesel::insert_into(settlements::table)
+        .values(&settlement)
+        .returning(Settlement::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn find_settlement(
+    conn: &mut Connection,
+    household_id: Uuid,
+    settlement_id: Uuid,
+) -> Result<Settlement, APIError> {
+    Settlement::all()
+        .filter(settlements::id.eq(settlement_id))
+        .filter(settlements::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(SettlementNotFound).build())
+}
+
+pub async fn find_settlements_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<Settlement>, APIError> {
+    Settlement::all()
+        .filter(settlements::household_id.eq(household_id))
+        .order(settlements::created_at.desc())
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn delete_settlement(conn: &mut Connection, settlement_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(settlements::table.find(settlement_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+/// Ensures the current user can remove a settlement. Owners, admins, the members involved and
+/// the creator of a settlement can.
+pub fn require_settlement_editor(
+    settlement: &Settlement,
+    membership: &HouseholdMember,
+    user_id: Uuid,
+) -> Result<(), APIError> {
+    if membership.role.can_manage()
+        || settlement.from_user_id == user_id
+        || settlement.to_user_id == user_id
+        || settlement.created_by == Some(user_id)
+    {
+        return Ok(());
+    }
+
+    Err(APIErrorBuilder::new(Forbidden)
+        .detail("Only owners, admins, the members involved and the creator of a settlement can remove it.")
+        .build())
+}
@@ -1,28 +1,51 @@
-use super::error::{APIError, APIErrorBuilder, ErrorType::Unauthorized};
+use super::error::{
+    APIError, APIErrorBuilder,
+    ErrorType::{MethodNotAllowed, Unauthorized},
+};
+use crate::api::auth::keys::KeySet;
 use crate::AppState;
 use axum::{
     extract::State,
-    http::{header, HeaderMap, HeaderValue, Request},
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use chrono::{DateTime, NaiveDateTime};
 use pasetors::{
     claims::{Claims, ClaimsValidationRules},
-    keys::AsymmetricPublicKey,
+    footer::Footer,
     public,
     token::{TrustedToken, UntrustedToken},
     version4::V4,
     Public,
 };
-use tracing::info;
+use std::future::Future;
+use tower_http::request_id::RequestId;
 use uuid::Uuid;
 
+tokio::task_local! {
+    /// The id of the request being handled, for errors to refer to.
+    static REQUEST_ID: String;
+
+    /// Whether errors include their causes, from the settings of the server.
+    static EXPOSE_CAUSES: bool;
+}
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
 #[derive(Clone)]
 pub struct CurrentUser {
     pub id: Uuid,
     pub email: String,
     pub first_name: String,
     pub last_name: String,
+    pub email_verified: bool,
+    /// The session the token was issued for. Missing from tokens issued before sessions were tracked.
+    pub session_id: Option<Uuid>,
+    /// The `jti` claim of the token.
+    pub token_id: Uuid,
+    pub issued_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
 }
 
 /// Middleware that validates a PASETO token and adds user info to the request.
@@ -32,26 +55,151 @@ pub async fn auth<B>(
     next: Next<B>,
 ) -> Result<Response, APIError> {
     let untrusted_token = get_token(req.headers())?;
-    let trusted_token = validate_token(untrusted_token, &state.settings.auth.public_key)?;
+    let trusted_token = validate_token(untrusted_token, &state.keys)?;
 
     let current_user = get_user_details(&trusted_token)?;
+    check_not_revoked(&state, &current_user).await?;
     req.extensions_mut().insert(current_user);
 
     Ok(next.run(req).await)
 }
 
+/// Middleware that gives requests using a method a route doesn't support an error response like any other.
+///
+/// axum answers these itself with an empty body, listing the supported methods in the `Allow` header.
+pub async fn method_not_allowed(response: Response) -> Response {
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+        return response;
+    }
+
+    let mut error = APIErrorBuilder::new(MethodNotAllowed)
+        .build()
+        .into_response();
+    if let Some(allow) = response.headers().get(header::ALLOW) {
+        error.headers_mut().insert(header::ALLOW, allow.clone());
+    }
+    error
+}
+
+/// The longest request id a client can give. UUIDs and most tracing ids are well within this.
+const MAX_REQUEST_ID_LENGTH: usize = 64;
+
+/// Middleware that drops a request id sent by the client unless it looks like a UUID or similar token.
+///
+/// The id ends up in logs and error responses, so anything else is replaced by a fresh id from `SetRequestIdLayer`.
+pub async fn discard_untrusted_request_id<B>(mut req: Request<B>, next: Next<B>) -> Response {
+    let untrusted = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .is_some_and(|id| !is_token_shaped(id.as_bytes()));
+    if untrusted {
+        req.headers_mut().remove(REQUEST_ID_HEADER);
+    }
+
+    next.run(req).await
+}
+
+fn is_token_shaped(id: &[u8]) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id
+            .iter()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'))
+}
+
+/// Middleware that makes the id of a request available to everything that handles it.
+///
+/// The id is set by `SetRequestIdLayer`, which keeps an id the client sent in the `X-Request-Id` header if
+/// `discard_untrusted_request_id` let it through.
+pub async fn request_id<B>(req: Request<B>, next: Next<B>) -> Response {
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .map(|id| id.to_string());
+
+    match request_id {
+        Some(request_id) => REQUEST_ID.scope(request_id, next.run(req)).await,
+        None => next.run(req).await,
+    }
+}
+
+/// The id of the request being handled, if there is one.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Middleware that makes errors include their causes if the server is configured to expose them.
+pub async fn expose_causes<B>(
+    State(state): State<AppState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    exposing_causes(state.settings.errors.expose_causes, next.run(req)).await
+}
+
+/// Runs a future in which errors include their causes or not, for work that happens outside of a request.
+pub fn exposing_causes<F: Future>(expose: bool, future: F) -> impl Future<Output = F::Output> {
+    EXPOSE_CAUSES.scope(expose, future)
+}
+
+/// Whether errors should include their causes. Causes are hidden unless the server says otherwise.
+pub fn causes_exposed() -> bool {
+    EXPOSE_CAUSES.try_with(|expose| *expose).unwrap_or(false)
+}
+
+/// Authenticates the handshake of a WebSocket connection.
+///
+/// Browsers can't set headers on WebSocket requests, so the token can also be given in the query string.
+pub async fn authenticate_socket(
+    state: &AppState,
+    headers: &HeaderMap<HeaderValue>,
+    query_token: Option<&str>,
+) -> Result<CurrentUser, APIError> {
+    let untrusted_token = match query_token {
+        Some(token) => parse_token(token)?,
+        None => get_token(headers)?,
+    };
+    let trusted_token = validate_token(untrusted_token, &state.keys)?;
+
+    let current_user = get_user_details(&trusted_token)?;
+    check_not_revoked(state, &current_user).await?;
+
+    Ok(current_user)
+}
+
+/// Fails if the token a user signed in with has been revoked.
+pub async fn check_not_revoked(state: &AppState, user: &CurrentUser) -> Result<(), APIError> {
+    if state
+        .revocations
+        .is_revoked(&state.database_pool, user)
+        .await?
+    {
+        return Err(APIErrorBuilder::new(Unauthorized)
+            .detail("The token you provided has been revoked.")
+            .build());
+    }
+
+    Ok(())
+}
+
 fn validate_token(
     token: UntrustedToken<Public, V4>,
-    public_key: &str,
+    keys: &KeySet,
 ) -> Result<TrustedToken, APIError> {
     let mut rules = ClaimsValidationRules::new();
     rules.validate_issuer_with("domus-api.jacksonc.dev");
     rules.validate_audience_with("domus.jacksonc.dev");
 
-    let key = AsymmetricPublicKey::<V4>::try_from(public_key)
-        .map_err(|e| APIErrorBuilder::from_error(e).build())?;
+    let key = get_key_id(&token)
+        .and_then(|id| keys.verification_key(&id))
+        .ok_or_else(|| {
+            APIErrorBuilder::new(Unauthorized)
+                .detail("The token you provided was not signed by a trusted key.")
+                .build()
+        })?;
 
-    public::verify(&key, &token, &rules, None, None).map_err(|e| {
+    public::verify(&key.key, &token, &rules, Some(&key.footer), None).map_err(|e| {
         APIErrorBuilder::new(Unauthorized)
             .cause(e)
             .detail("The token you provided is not trusted.")
@@ -59,6 +207,17 @@ fn validate_token(
     })
 }
 
+/// Reads the PASERK id of the key a token claims to be signed with from its footer.
+fn get_key_id(token: &UntrustedToken<Public, V4>) -> Option<String> {
+    let mut footer = Footer::new();
+    footer.parse_bytes(token.untrusted_footer()).ok()?;
+
+    footer
+        .get_claim("kid")
+        .and_then(|kid| kid.as_str())
+        .map(|kid| kid.to_string())
+}
+
 fn get_token(headers: &HeaderMap<HeaderValue>) -> Result<UntrustedToken<Public, V4>, APIError> {
     let token = headers
         .get(header::AUTHORIZATION)
@@ -70,6 +229,10 @@ fn get_token(headers: &HeaderMap<HeaderValue>) -> Result<UntrustedToken<Public,
                 .build(),
         )?;
 
+    parse_token(token)
+}
+
+fn parse_token(token: &str) -> Result<UntrustedToken<Public, V4>, APIError> {
     UntrustedToken::try_from(token).map_err(|e| {
         APIErrorBuilder::new(Unauthorized)
             .cause(e)
@@ -89,12 +252,28 @@ fn get_user_details(token: &TrustedToken) -> Result<CurrentUser, APIError> {
     let email = get_claim(claims, "email")?;
     let first_name = get_claim(claims, "first_name")?;
     let last_name = get_claim(claims, "last_name")?;
+    let session_id = claims
+        .get_claim("sid")
+        .and_then(|c| c.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok());
+    let email_verified = claims
+        .get_claim("email_verified")
+        .and_then(|c| c.as_bool())
+        .unwrap_or(false);
+    let token_id = get_uuid_claim(claims, "jti")?;
+    let issued_at = get_time_claim(claims, "iat")?;
+    let expires_at = get_time_claim(claims, "exp")?;
 
     Ok(CurrentUser {
         id,
         email,
         first_name,
         last_name,
+        email_verified,
+        session_id,
+        token_id,
+        issued_at,
+        expires_at,
     })
 }
 
@@ -102,7 +281,6 @@ fn get_user_id(claims: &Claims) -> Result<Uuid, APIError> {
     let sub_claim = get_claim(claims, "sub")?;
 
     Uuid::parse_str(sub_claim.as_str()).map_err(|e| {
-        info!(error = %e, "Token claims contained an invalid subject.");
         APIErrorBuilder::new(Unauthorized)
             .cause(e)
             .detail("The token you provided is invalid.")
@@ -110,14 +288,38 @@ fn get_user_id(claims: &Claims) -> Result<Uuid, APIError> {
     })
 }
 
+fn get_uuid_claim(claims: &Claims, claim_name: &str) -> Result<Uuid, APIError> {
+    let claim = get_claim(claims, claim_name)?;
+
+    Uuid::parse_str(claim.as_str()).map_err(|e| {
+        APIErrorBuilder::new(Unauthorized)
+            .cause(e)
+            .detail("The token you provided is invalid.")
+            .build()
+    })
+}
+
+fn get_time_claim(claims: &Claims, claim_name: &str) -> Result<NaiveDateTime, APIError> {
+    let claim = get_claim(claims, claim_name)?;
+
+    DateTime::parse_from_rfc3339(claim.as_str())
+        .map(|time| time.naive_utc())
+        .map_err(|e| {
+            APIErrorBuilder::new(Unauthorized)
+                .cause(e)
+                .detail("The token you provided is invalid.")
+                .build()
+        })
+}
+
 fn get_claim(claims: &Claims, claim_name: &str) -> Result<String, APIError> {
     claims
         .get_claim(claim_name)
         .and_then(|c| c.as_str())
         .map(|s| s.to_string())
         .ok_or_else(|| {
-            info!(claims = ?claims, claim_name = claim_name, "claim missing from token.");
             APIErrorBuilder::new(Unauthorized)
+                .cause(format!("claim {} is missing from the token", claim_name))
                 .detail("The token you provided is invalid.")
                 .build()
         })
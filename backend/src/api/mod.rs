@@ -1,12 +1,76 @@
 use crate::AppState;
+use axum::routing::get;
 use axum::Router;
+use error::{APIError, APIErrorBuilder, ErrorType::NotFound};
 
 pub mod api_docs;
 pub mod auth;
-mod error;
-mod middleware;
+pub mod bill_templates;
+pub mod bills;
+pub mod calendar;
+pub mod chat;
+pub mod chores;
+pub mod documents;
+pub mod error;
+pub mod households;
+pub mod invites;
+pub mod ledger;
+pub mod middleware;
+pub mod shopping;
 mod utils;
 
 pub fn get_router(state: AppState) -> Router<AppState> {
-    Router::new().nest("/auth", auth::get_router(state))
+    Router::new()
+        .nest("/auth", auth::get_router(state.clone()))
+        .nest("/households", households::get_router(state.clone()))
+        .nest(
+            "/households/:household_id/invites",
+            invites::get_household_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/chores",
+            chores::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/bills/templates",
+            bill_templates::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/bills",
+            bills::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/ledger",
+            ledger::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/shopping",
+            shopping::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/calendar",
+            calendar::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/chat",
+            chat::get_router(state.clone()),
+        )
+        .nest(
+            "/households/:household_id/documents",
+            documents::get_router(state.clone()),
+        )
+        .nest("/invites", invites::get_router(state))
+}
+
+/// Routes served from the root of the server, outside of the versioned api.
+pub fn get_well_known_router() -> Router<AppState> {
+    Router::new().route(
+        "/.well-known/paserk-keys",
+        get(auth::controllers::get_public_keys),
+    )
+}
+
+/// Answers requests for paths that don't match any route.
+pub async fn not_found() -> APIError {
+    APIErrorBuilder::new(NotFound).build()
 }
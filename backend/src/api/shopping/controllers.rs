@@ -0,0 +1,588 @@
+use super::models::{
+    AddShoppingItemRequest, CheckShoppingItemsRequest, CheckShoppingItemsResponse,
+    CreateShoppingListRequest, ShoppingItemResponse, ShoppingListResponse,
+    UpdateShoppingItemRequest, UpdateShoppingListRequest,
+};
+use super::utils::{
+    add_item, check_items, clear_checked_items, create_list, delete_item as remove_item,
+    delete_list, find_items, find_items_for_lists, find_list, find_list_with_items,
+    find_lists_for_household, update_item as save_item, update_list,
+};
+use crate::api::bills::utils::load_bill_details;
+use crate::api::error::APIError;
+use crate::api::households::utils::{
+    find_household_for_user, find_household_member_ids, parse_member_id,
+};
+use crate::api::middleware::CurrentUser;
+use crate::api::utils::db::get_db_connection;
+use crate::api::utils::extract::Path;
+use crate::api::utils::friendly_id::{parse_friendly_id, ItemIdType};
+use crate::api::utils::validation::ValidatedJson;
+use crate::db::bill::{BillStatus, NewBill, SplitMethod};
+use crate::db::shopping::{
+    NewShoppingItem, NewShoppingList, ShoppingItemChangeset, ShoppingListChangeset,
+};
+use crate::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use tracing::info;
+use uuid::Uuid;
+
+/// Create a shopping list
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/shopping/lists",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CreateShoppingListRequest
+    ),
+    responses(
+        (status = 201, description = "Created shopping list successfully", body = ShoppingListResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn create_shopping_list(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+    ValidatedJson(payload): ValidatedJson<CreateShoppingListRequest>,
+) -> Result<(StatusCode, Json<ShoppingListResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    info!(user_id = %current_user.id, household_id = %household_id, "creating shopping list");
+    let list = create_list(
+        &mut conn,
+        NewShoppingList {
+            household_id,
+            created_by: Some(current_user.id),
+            name: payload.name,
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json((list, Vec::new()).into())))
+}
+
+/// List the shopping lists of a household
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/shopping/lists",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id")
+    ),
+    responses(
+        (status = 200, description = "Success", body = [ShoppingListResponse]),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household not found", body = APIError),
+    )
+)]
+pub async fn list_shopping_lists(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path(household_id): Path<String>,
+) -> Result<(StatusCode, Json<Vec<ShoppingListResponse>>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let lists = find_lists_for_household(&mut conn, household_id).await?;
+    let items = find_items_for_lists(&mut conn, &lists).await?;
+
+    let response = lists
+        .into_iter()
+        .zip(items)
+        .map(ShoppingListResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+/// Get a shopping list
+#[utoipa::path(
+    get,
+    path = "/households/{household_id}/shopping/lists/{list_id}",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = ShoppingListResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or shopping list not found", body = APIError),
+    )
+)]
+pub async fn get_shopping_list(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id)): Path<(String, String)>,
+) -> Result<(StatusCode, Json<ShoppingListResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+
+    let list = find_list_with_items(&mut conn, household_id, list_id).await?;
+    Ok((StatusCode::OK, Json(list.into())))
+}
+
+/// Rename a shopping list
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/shopping/lists/{list_id}",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateShoppingListRequest
+    ),
+    responses(
+        (status = 200, description = "Updated shopping list successfully", body = ShoppingListResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or shopping list not found", body = APIError),
+    )
+)]
+pub async fn update_shopping_list(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateShoppingListRequest>,
+) -> Result<(StatusCode, Json<ShoppingListResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    if payload.name.is_some() {
+        let changes = ShoppingListChangeset { name: payload.name };
+        update_list(&mut conn, list_id, changes).await?;
+    }
+
+    let list = find_list_with_items(&mut conn, household_id, list_id).await?;
+    Ok((StatusCode::OK, Json(list.into())))
+}
+
+/// Delete a shopping list
+///
+/// All items on the list are deleted with it.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/shopping/lists/{list_id}",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+    ),
+    responses(
+        (status = 204, description = "Deleted shopping list successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or shopping list not found", body = APIError),
+    )
+)]
+pub async fn delete_shopping_list(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    info!(user_id = %current_user.id, list_id = %list_id, "deleting shopping list");
+    delete_list(&mut conn, list_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Add an item to a shopping list
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/shopping/lists/{list_id}/items",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = AddShoppingItemRequest
+    ),
+    responses(
+        (status = 201, description = "Added item successfully", body = ShoppingItemResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or shopping list not found", body = APIError),
+    )
+)]
+pub async fn add_shopping_item(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<AddShoppingItemRequest>,
+) -> Result<(StatusCode, Json<ShoppingItemResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    let item = add_item(
+        &mut conn,
+        NewShoppingItem {
+            list_id,
+            name: payload.name,
+            quantity: payload.quantity,
+            unit: payload.unit,
+            category: payload.category,
+            added_by: Some(current_user.id),
+        },
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(item.into())))
+}
+
+/// Update an item on a shopping list
+#[utoipa::path(
+    patch,
+    path = "/households/{household_id}/shopping/lists/{list_id}/items/{item_id}",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+        ("item_id" = String, Path, description = "The shopping list item id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = UpdateShoppingItemRequest
+    ),
+    responses(
+        (status = 200, description = "Updated item successfully", body = ShoppingItemResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, shopping list or item not found", body = APIError),
+    )
+)]
+pub async fn update_shopping_item(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id, item_id)): Path<(String, String, String)>,
+    ValidatedJson(payload): ValidatedJson<UpdateShoppingItemRequest>,
+) -> Result<(StatusCode, Json<ShoppingItemResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+    let item_id = parse_friendly_id(&item_id, ItemIdType::ShoppingItem)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    let changes = ShoppingItemChangeset {
+        name: payload.name,
+        quantity: payload.quantity,
+        unit: payload.unit,
+        category: payload.category,
+        ..Default::default()
+    };
+    let item = if has_changes(&changes) {
+        save_item(&mut conn, list_id, item_id, changes).await?
+    } else {
+        find_items(&mut conn, list_id, &[item_id]).await?.remove(0)
+    };
+
+    Ok((StatusCode::OK, Json(item.into())))
+}
+
+/// Remove an item from a shopping list
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/shopping/lists/{list_id}/items/{item_id}",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+        ("item_id" = String, Path, description = "The shopping list item id"),
+    ),
+    responses(
+        (status = 204, description = "Removed item successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, shopping list or item not found", body = APIError),
+    )
+)]
+pub async fn delete_shopping_item(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id, item_id)): Path<(String, String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+    let item_id = parse_friendly_id(&item_id, ItemIdType::ShoppingItem)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    remove_item(&mut conn, list_id, item_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check off an item on a shopping list
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/shopping/lists/{list_id}/items/{item_id}/check",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+        ("item_id" = String, Path, description = "The shopping list item id"),
+    ),
+    responses(
+        (status = 200, description = "Checked off item successfully", body = ShoppingItemResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, shopping list or item not found", body = APIError),
+    )
+)]
+pub async fn check_shopping_item(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id, item_id)): Path<(String, String, String)>,
+) -> Result<(StatusCode, Json<ShoppingItemResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+    let item_id = parse_friendly_id(&item_id, ItemIdType::ShoppingItem)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    let items = find_items(&mut conn, list_id, &[item_id]).await?;
+    let (mut items, _) = check_items(&mut conn, items, current_user.id, None).await?;
+
+    Ok((StatusCode::OK, Json(items.remove(0).into())))
+}
+
+/// Uncheck an item on a shopping list
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/shopping/lists/{list_id}/items/{item_id}/uncheck",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+        ("item_id" = String, Path, description = "The shopping list item id"),
+    ),
+    responses(
+        (status = 200, description = "Unchecked item successfully", body = ShoppingItemResponse),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, shopping list or item not found", body = APIError),
+    )
+)]
+pub async fn uncheck_shopping_item(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id, item_id)): Path<(String, String, String)>,
+) -> Result<(StatusCode, Json<ShoppingItemResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+    let item_id = parse_friendly_id(&item_id, ItemIdType::ShoppingItem)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    let changes = ShoppingItemChangeset {
+        checked_by: Some(None),
+        checked_at: Some(None),
+        ..Default::default()
+    };
+    let item = save_item(&mut conn, list_id, item_id, changes).await?;
+
+    Ok((StatusCode::OK, Json(item.into())))
+}
+
+/// Check off several items at once
+///
+/// Optionally creates a draft bill for the purchase, with the items as line items. The draft can be
+/// split between members once the total is known.
+#[utoipa::path(
+    post,
+    path = "/households/{household_id}/shopping/lists/{list_id}/check",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+    ),
+    request_body(
+        content_type = "application/json",
+        content = CheckShoppingItemsRequest
+    ),
+    responses(
+        (status = 200, description = "Checked off items successfully", body = CheckShoppingItemsResponse),
+        (status = 400, description = "Bad Request", body = APIError),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household, shopping list or item not found", body = APIError),
+    )
+)]
+pub async fn check_shopping_items(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id)): Path<(String, String)>,
+    ValidatedJson(payload): ValidatedJson<CheckShoppingItemsRequest>,
+) -> Result<(StatusCode, Json<CheckShoppingItemsResponse>), APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+
+    let mut item_ids = payload
+        .item_ids
+        .iter()
+        .map(|id| parse_friendly_id(id, ItemIdType::ShoppingItem))
+        .collect::<Result<Vec<Uuid>, _>>()?;
+    item_ids.sort();
+    item_ids.dedup();
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    let list = find_list(&mut conn, household_id, list_id).await?;
+    let items = find_items(&mut conn, list_id, &item_ids).await?;
+
+    let draft = match payload.bill {
+        Some(bill) => {
+            let member_ids = find_household_member_ids(&mut conn, household_id).await?;
+            let payer_id = match bill.payer_id {
+                Some(ref payer_id) => parse_member_id(payer_id, &member_ids, "bill.payer_id")?,
+                None => current_user.id,
+            };
+
+            Some(NewBill {
+                household_id,
+                created_by: Some(current_user.id),
+                payer_id,
+                description: bill.description.unwrap_or(list.name),
+                amount: bill.amount.unwrap_or(0),
+                currency: bill.currency,
+                due_date: None,
+                split_method: SplitMethod::Equal,
+                template_id: None,
+                status: BillStatus::Draft,
+            })
+        }
+        None => None,
+    };
+
+    info!(user_id = %current_user.id, list_id = %list_id, count = items.len(), "checking shopping items");
+    let (items, bill) = check_items(&mut conn, items, current_user.id, draft).await?;
+    let bill = match bill {
+        Some(bill) => Some(load_bill_details(&mut conn, bill).await?.into()),
+        None => None,
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(CheckShoppingItemsResponse {
+            items: items.into_iter().map(ShoppingItemResponse::from).collect(),
+            bill,
+        }),
+    ))
+}
+
+/// Clear checked off items
+///
+/// Removes every item that has been checked off from the list.
+#[utoipa::path(
+    delete,
+    path = "/households/{household_id}/shopping/lists/{list_id}/checked",
+    tag = "shopping",
+    security(
+        ("api_token" = [])
+    ),
+    params(
+        ("household_id" = String, Path, description = "The household id"),
+        ("list_id" = String, Path, description = "The shopping list id"),
+    ),
+    responses(
+        (status = 204, description = "Cleared checked off items successfully"),
+        (status = 401, description = "User not signed in", body = APIError),
+        (status = 404, description = "Household or shopping list not found", body = APIError),
+    )
+)]
+pub async fn clear_checked_shopping_items(
+    State(state): State<AppState>,
+    Extension(current_user): Extension<CurrentUser>,
+    Path((household_id, list_id)): Path<(String, String)>,
+) -> Result<StatusCode, APIError> {
+    let household_id = parse_friendly_id(&household_id, ItemIdType::Household)?;
+    let list_id = parse_friendly_id(&list_id, ItemIdType::ShoppingList)?;
+
+    let mut conn = get_db_connection(&state.database_pool).await?;
+    find_household_for_user(&mut conn, household_id, current_user.id).await?;
+    find_list(&mut conn, household_id, list_id).await?;
+
+    let count = clear_checked_items(&mut conn, list_id).await?;
+    info!(user_id = %current_user.id, list_id = %list_id, count, "cleared checked shopping items");
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn has_changes(changes: &ShoppingItemChangeset) -> bool {
+    changes.name.is_some()
+        || changes.quantity.is_some()
+        || changes.unit.is_some()
+        || changes.category.is_some()
+}
@@ -0,0 +1,46 @@
+use super::middleware::auth;
+use crate::AppState;
+use axum::routing::{delete, get, patch, post};
+use axum::{middleware, Router};
+use controllers::{
+    add_shopping_item, check_shopping_item, check_shopping_items, clear_checked_shopping_items,
+    create_shopping_list, delete_shopping_item, delete_shopping_list, get_shopping_list,
+    list_shopping_lists, uncheck_shopping_item, update_shopping_item, update_shopping_list,
+};
+
+pub mod controllers;
+pub mod models;
+mod utils;
+
+pub fn get_router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/lists",
+            post(create_shopping_list).get(list_shopping_lists),
+        )
+        .route(
+            "/lists/:list_id",
+            get(get_shopping_list)
+                .patch(update_shopping_list)
+                .delete(delete_shopping_list),
+        )
+        .route("/lists/:list_id/items", post(add_shopping_item))
+        .route(
+            "/lists/:list_id/items/:item_id",
+            patch(update_shopping_item).delete(delete_shopping_item),
+        )
+        .route(
+            "/lists/:list_id/items/:item_id/check",
+            post(check_shopping_item),
+        )
+        .route(
+            "/lists/:list_id/items/:item_id/uncheck",
+            post(uncheck_shopping_item),
+        )
+        .route("/lists/:list_id/check", post(check_shopping_items))
+        .route(
+            "/lists/:list_id/checked",
+            delete(clear_checked_shopping_items),
+        )
+        .route_layer(middleware::from_fn_with_state(state, auth))
+}
@@ -0,0 +1,173 @@
+use crate::api::bills::currency::validate_currency;
+use crate::api::bills::models::BillResponse;
+use crate::api::utils::friendly_id::{ItemIdType, ToFriendlyId};
+use crate::db::shopping::{ShoppingItem, ShoppingList};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CreateShoppingListRequest {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "Groceries", min_length = 1, max_length = 64)]
+    pub name: String,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateShoppingListRequest {
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "Groceries", min_length = 1, max_length = 64)]
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ShoppingListResponse {
+    #[schema(example = "shopping_list|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "household|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub household_id: String,
+    #[schema(example = "Groceries")]
+    pub name: String,
+    /// Items still to buy come first, grouped by category.
+    pub items: Vec<ShoppingItemResponse>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<(ShoppingList, Vec<ShoppingItem>)> for ShoppingListResponse {
+    fn from((list, items): (ShoppingList, Vec<ShoppingItem>)) -> Self {
+        Self {
+            id: list.id.to_friendly_id(ItemIdType::ShoppingList),
+            household_id: list.household_id.to_friendly_id(ItemIdType::Household),
+            name: list.name,
+            items: items.into_iter().map(ShoppingItemResponse::from).collect(),
+            created_by: list
+                .created_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            created_at: list.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct AddShoppingItemRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Milk", min_length = 1, max_length = 128)]
+    pub name: String,
+
+    #[validate(range(min = 0.001))]
+    #[schema(example = 2.0)]
+    pub quantity: Option<f64>,
+
+    #[validate(length(min = 1, max = 16))]
+    #[schema(example = "L", min_length = 1, max_length = 16)]
+    pub unit: Option<String>,
+
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "Dairy", min_length = 1, max_length = 64)]
+    pub category: Option<String>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct UpdateShoppingItemRequest {
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Milk", min_length = 1, max_length = 128)]
+    pub name: Option<String>,
+
+    #[validate(range(min = 0.001))]
+    #[schema(example = 2.0)]
+    pub quantity: Option<f64>,
+
+    #[validate(length(min = 1, max = 16))]
+    #[schema(example = "L", min_length = 1, max_length = 16)]
+    pub unit: Option<String>,
+
+    #[validate(length(min = 1, max = 64))]
+    #[schema(example = "Dairy", min_length = 1, max_length = 64)]
+    pub category: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ShoppingItemResponse {
+    #[schema(example = "shopping_item|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub id: String,
+    #[schema(example = "shopping_list|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub list_id: String,
+    #[schema(example = "Milk")]
+    pub name: String,
+    #[schema(example = 2.0)]
+    pub quantity: Option<f64>,
+    #[schema(example = "L")]
+    pub unit: Option<String>,
+    #[schema(example = "Dairy")]
+    pub category: Option<String>,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub added_by: Option<String>,
+    pub checked: bool,
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub checked_by: Option<String>,
+    pub checked_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<ShoppingItem> for ShoppingItemResponse {
+    fn from(item: ShoppingItem) -> Self {
+        Self {
+            id: item.id.to_friendly_id(ItemIdType::ShoppingItem),
+            list_id: item.list_id.to_friendly_id(ItemIdType::ShoppingList),
+            name: item.name,
+            quantity: item.quantity,
+            unit: item.unit,
+            category: item.category,
+            added_by: item.added_by.map(|id| id.to_friendly_id(ItemIdType::User)),
+            checked: item.checked_at.is_some(),
+            checked_by: item
+                .checked_by
+                .map(|id| id.to_friendly_id(ItemIdType::User)),
+            checked_at: item.checked_at,
+            created_at: item.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct CheckShoppingItemsRequest {
+    /// The items that were bought.
+    #[validate(length(min = 1, max = 200))]
+    #[schema(example = json!(["shopping_item|4XbFzGDGpKfKMSzrV8MQtH"]))]
+    pub item_ids: Vec<String>,
+
+    /// If given, a draft bill is created for the purchase with the items as line items.
+    #[validate]
+    pub bill: Option<BillDraftRequest>,
+}
+
+#[derive(Deserialize, Validate, ToSchema, Debug)]
+pub struct BillDraftRequest {
+    /// Defaults to the name of the shopping list.
+    #[validate(length(min = 1, max = 128))]
+    #[schema(example = "Groceries", min_length = 1, max_length = 128)]
+    pub description: Option<String>,
+
+    /// What the purchase cost, in the minor unit of the currency (e.g. cents), if it's known yet.
+    #[validate(range(min = 1))]
+    #[schema(example = 8_450, minimum = 1)]
+    pub amount: Option<i64>,
+
+    /// An ISO 4217 currency code.
+    #[validate(custom = "validate_currency")]
+    #[schema(example = "NZD", min_length = 3, max_length = 3)]
+    pub currency: String,
+
+    /// The member who paid. Defaults to the current user.
+    #[schema(example = "user|4XbFzGDGpKfKMSzrV8MQtH")]
+    pub payer_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CheckShoppingItemsResponse {
+    pub items: Vec<ShoppingItemResponse>,
+    /// The draft bill created for the purchase, if one was asked for.
+    pub bill: Option<BillResponse>,
+}
@@ -0,0 +1,264 @@
+use crate::api::error::ErrorType::{ShoppingItemNotFound, ShoppingListNotFound};
+use crate::api::error::{APIError, APIErrorBuilder};
+use crate::db::bill::{Bill, NewBill, NewBillItem};
+use crate::db::database::Connection;
+use crate::db::schema::{bill_items, bills, shopping_items, shopping_lists};
+use crate::db::shopping::{
+    NewShoppingItem, NewShoppingList, ShoppingItem, ShoppingItemChangeset, ShoppingList,
+    ShoppingListChangeset,
+};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use uuid::Uuid;
+
+pub async fn create_list(
+    conn: &mut Connection,
+    list: NewShoppingList,
+) -> Result<ShoppingList, APIError> {
+    diesel::insert_into(shopping_lists::table)
+        .values(&list)
+        .returning(ShoppingList::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+pub async fn find_list(
+    conn: &mut Connection,
+    household_id: Uuid,
+    list_id: Uuid,
+) -> Result<ShoppingList, APIError> {
+    ShoppingList::all()
+        .filter(shopping_lists::id.eq(list_id))
+        .filter(shopping_lists::household_id.eq(household_id))
+        .first(conn)
+        .await
+        .optional()
+        .map_err(APIError::from)?
+        .ok_or_else(|| APIErrorBuilder::new(ShoppingListNotFound).build())
+}
+
+pub async fn find_lists_for_household(
+    conn: &mut Connection,
+    household_id: Uuid,
+) -> Result<Vec<ShoppingList>, APIError> {
+    ShoppingList::all()
+        .filter(shopping_lists::household_id.eq(household_id))
+        .order(shopping_lists::created_at)
+        .load(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the items of the given lists, grouped by list in the same order.
+///
+/// Items still to buy come first, grouped by category, then in the order they were added.
+pub async fn find_items_for_lists(
+    conn: &mut Connection,
+    lists: &[ShoppingList],
+) -> Result<Vec<Vec<ShoppingItem>>, APIError> {
+    let items = ShoppingItem::belonging_to(lists)
+        .select(ShoppingItem::as_select())
+        .order((
+            shopping_items::checked_at.is_not_null(),
+            shopping_items::category.asc().nulls_last(),
+            shopping_items::created_at,
+        ))
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(items.grouped_by(lists))
+}
+
+/// Finds a list along with its items.
+pub async fn find_list_with_items(
+    conn: &mut Connection,
+    household_id: Uuid,
+    list_id: Uuid,
+) -> Result<(ShoppingList, Vec<ShoppingItem>), APIError> {
+    let list = find_list(conn, household_id, list_id).await?;
+    let items = find_items_for_lists(conn, std::slice::from_ref(&list))
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    Ok((list, items))
+}
+
+pub async fn update_list(
+    conn: &mut Connection,
+    list_id: Uuid,
+    changes: ShoppingListChangeset,
+) -> Result<(), APIError> {
+    diesel::update(shopping_lists::table.find(list_id))
+        .set(&changes)
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+pub async fn delete_list(conn: &mut Connection, list_id: Uuid) -> Result<(), APIError> {
+    diesel::delete(shopping_lists::table.find(list_id))
+        .execute(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    Ok(())
+}
+
+pub async fn add_item(
+    conn: &mut Connection,
+    item: NewShoppingItem,
+) -> Result<ShoppingItem, APIError> {
+    diesel::insert_into(shopping_items::table)
+        .values(&item)
+        .returning(ShoppingItem::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(APIError::from)
+}
+
+/// Finds the given items of a list, failing if any of them aren't on it.
+pub async fn find_items(
+    conn: &mut Connection,
+    list_id: Uuid,
+    item_ids: &[Uuid],
+) -> Result<Vec<ShoppingItem>, APIError> {
+    let items: Vec<ShoppingItem> = ShoppingItem::all()
+        .filter(shopping_items::list_id.eq(list_id))
+        .filter(shopping_items::id.eq_any(item_ids))
+        .order(shopping_items::created_at)
+        .load(conn)
+        .await
+        .map_err(APIError::from)?;
+
+    if items.len() != item_ids.len() {
+        return Err(APIErrorBuilder::new(ShoppingItemNotFound).build());
+    }
+
+    Ok(items)
+}
+
+pub async fn update_item(
+    conn: &mut Connection,
+    list_id: Uuid,
+    item_id: Uuid,
+    changes: ShoppingItemChangeset,
+) -> Result<ShoppingItem, APIError> {
+    diesel::update(
+        shopping_items::table
+            .filter(shopping_items::id.eq(item_id))
+            .filter(shopping_items::list_id.eq(list_id)),
+    )
+    .set(&changes)
+    .returning(ShoppingItem::as_returning())
+    .get_result(conn)
+    .await
+    .optional()
+    .map_err(APIError::from)?
+    .ok_or_else(|| APIErrorBuilder::new(ShoppingItemNotFound).build())
+}
+
+pub async fn delete_item(
+    conn: &mut Connection,
+    list_id: Uuid,
+    item_id: Uuid,
+) -> Result<(), APIError> {
+    let deleted = diesel::delete(
+        shopping_items::table
+            .filter(shopping_items::id.eq(item_id))
+            .filter(shopping_items::list_id.eq(list_id)),
+    )
+    .execute(conn)
+    .await
+    .map_err(APIError::from)?;
+
+    if deleted == 0 {
+        return Err(APIErrorBuilder::new(ShoppingItemNotFound).build());
+    }
+
+    Ok(())
+}
+
+/// Checks off the given items, creating a draft bill with the items as line items if one is given.
+///
+/// Items that were already checked off keep who checked them and when.
+pub async fn check_items(
+    conn: &mut Connection,
+    items: Vec<ShoppingItem>,
+    user_id: Uuid,
+    draft: Option<NewBill>,
+) -> Result<(Vec<ShoppingItem>, Option<Bill>), APIError> {
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        async move {
+            let item_ids: Vec<Uuid> = items.iter().map(|item| item.id).collect();
+            diesel::update(
+                shopping_items::table
+                    .filter(shopping_items::id.eq_any(&item_ids))
+                    .filter(shopping_items::checked_at.is_null()),
+            )
+            .set((
+                shopping_items::checked_by.eq(user_id),
+                shopping_items::checked_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .await?;
+
+            let bill = match draft {
+                Some(draft) => {
+                    let bill = diesel::insert_into(bills::table)
+                        .values(&draft)
+                        .returning(Bill::as_returning())
+                        .get_result(conn)
+                        .await?;
+
+                    let line_items: Vec<NewBillItem> = items
+                        .into_iter()
+                        .map(|item| NewBillItem {
+                            bill_id: bill.id,
+                            shopping_item_id: Some(item.id),
+                            name: item.name,
+                            quantity: item.quantity,
+                            unit: item.unit,
+                            category: item.category,
+                        })
+                        .collect();
+                    diesel::insert_into(bill_items::table)
+                        .values(&line_items)
+                        .execute(conn)
+                        .await?;
+
+                    Some(bill)
+                }
+                None => None,
+            };
+
+            let items = ShoppingItem::all()
+                .filter(shopping_items::id.eq_any(&item_ids))
+                .order(shopping_items::created_at)
+                .load(conn)
+                .await?;
+
+            Ok((items, bill))
+        }
+        .scope_boxed()
+    })
+    .await
+    .map_err(APIError::from)
+}
+
+/// Removes every checked off item from a list, returning how many were removed.
+pub async fn clear_checked_items(conn: &mut Connection, list_id: Uuid) -> Result<usize, APIError> {
+    diesel::delete(
+        shopping_items::table
+            .filter(shopping_items::list_id.eq(list_id))
+            .filter(shopping_items::checked_at.is_not_null()),
+    )
+    .execute(conn)
+    .await
+    .map_err(APIError::from)
+}
@@ -1,12 +1,11 @@
-use crate::api::error::{APIError, APIErrorBuilder};
+use crate::api::error::{APIError, APIErrorBuilder, ErrorType::ServiceUnavailable};
 use crate::db::database::{Connection, ConnectionPool};
-use tracing::error;
 
 pub(crate) async fn get_db_connection(pool: &ConnectionPool) -> Result<Connection, APIError> {
-    let connection: Connection = pool.get().await.map_err(|err| {
-        error!(error = %err, "failed to get database connection");
-        APIErrorBuilder::from_error(err).build()
-    })?;
+    let connection: Connection = pool
+        .get()
+        .await
+        .map_err(|err| APIErrorBuilder::new(ServiceUnavailable).cause(err).build())?;
 
     Ok(connection)
 }
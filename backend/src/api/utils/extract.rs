@@ -0,0 +1,111 @@
+//! Replacements for axum's extractors that reject requests with an `APIError`, so clients get the same problem
+//! details as for any other error.
+
+use crate::api::error::ErrorType::{MethodNotAllowed, ValidationError};
+use crate::api::error::{APIError, APIErrorBuilder};
+use axum::async_trait;
+use axum::body::{Bytes, HttpBody};
+use axum::extract::rejection::PathRejection;
+use axum::extract::{self, FromRequest, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{Request, StatusCode};
+use axum::BoxError;
+use serde::de::DeserializeOwned;
+
+pub struct Path<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = APIError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match extract::Path::<T>::from_request_parts(parts, state).await {
+            Ok(extract::Path(value)) => Ok(Self(value)),
+            Err(PathRejection::FailedToDeserializePathParams(e)) => {
+                Err(APIErrorBuilder::new(ValidationError)
+                    .detail(&e.body_text())
+                    .build())
+            }
+            // the route doesn't have the parameters the handler expects
+            Err(e) => Err(APIErrorBuilder::from_error(e).build()),
+        }
+    }
+}
+
+pub struct Query<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = APIError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let extract::Query(value) = extract::Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| {
+                APIErrorBuilder::new(ValidationError)
+                    .detail(&e.body_text())
+                    .build()
+            })?;
+
+        Ok(Self(value))
+    }
+}
+
+pub struct Multipart(pub extract::Multipart);
+
+#[async_trait]
+impl<S, B> FromRequest<S, B> for Multipart
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Into<Bytes>,
+    B::Error: Into<BoxError>,
+    S: Send + Sync,
+{
+    type Rejection = APIError;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        let multipart = extract::Multipart::from_request(req, state)
+            .await
+            .map_err(|e| {
+                APIErrorBuilder::new(ValidationError)
+                    .detail(&e.body_text())
+                    .build()
+            })?;
+
+        Ok(Self(multipart))
+    }
+}
+
+pub struct WebSocketUpgrade(pub extract::ws::WebSocketUpgrade);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for WebSocketUpgrade
+where
+    S: Send + Sync,
+{
+    type Rejection = APIError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let upgrade = extract::ws::WebSocketUpgrade::from_request_parts(parts, state)
+            .await
+            .map_err(|e| {
+                let error_type = match e.status() {
+                    StatusCode::METHOD_NOT_ALLOWED => MethodNotAllowed,
+                    _ => ValidationError,
+                };
+                APIErrorBuilder::new(error_type)
+                    .detail(&e.body_text())
+                    .build()
+            })?;
+
+        Ok(Self(upgrade))
+    }
+}
@@ -1,13 +1,40 @@
+use crate::api::error::{APIError, APIErrorBuilder, ErrorType::ValidationError};
 use uuid::Uuid;
 
 pub enum ItemIdType {
     User,
+    Household,
+    Invite,
+    Chore,
+    ChoreOccurrence,
+    Bill,
+    BillTemplate,
+    Settlement,
+    ShoppingList,
+    ShoppingItem,
+    Event,
+    ChatMessage,
+    Document,
+    Session,
 }
 
 impl ItemIdType {
     pub fn as_str(&self) -> &'static str {
         match self {
             ItemIdType::User => "user",
+            ItemIdType::Household => "household",
+            ItemIdType::Invite => "invite",
+            ItemIdType::Chore => "chore",
+            ItemIdType::ChoreOccurrence => "chore_occurrence",
+            ItemIdType::Bill => "bill",
+            ItemIdType::BillTemplate => "bill_template",
+            ItemIdType::Settlement => "settlement",
+            ItemIdType::ShoppingList => "shopping_list",
+            ItemIdType::ShoppingItem => "shopping_item",
+            ItemIdType::Event => "event",
+            ItemIdType::ChatMessage => "chat_message",
+            ItemIdType::Document => "document",
+            ItemIdType::Session => "session",
         }
     }
 }
@@ -22,3 +49,31 @@ impl ToFriendlyId for Uuid {
         format!("{}|{}", item_type.as_str(), id)
     }
 }
+
+pub trait FromFriendlyId: Sized {
+    fn from_friendly_id(id: &str, item_type: ItemIdType) -> Option<Self>;
+}
+
+impl FromFriendlyId for Uuid {
+    fn from_friendly_id(id: &str, item_type: ItemIdType) -> Option<Self> {
+        let (prefix, id) = id.split_once('|')?;
+        if prefix != item_type.as_str() {
+            return None;
+        }
+
+        base62::decode(id).ok().map(Uuid::from_u128)
+    }
+}
+
+/// Parses a friendly id supplied by the client, such as a path parameter.
+pub fn parse_friendly_id(id: &str, item_type: ItemIdType) -> Result<Uuid, APIError> {
+    let expected = item_type.as_str();
+
+    Uuid::from_friendly_id(id, item_type).ok_or_else(|| {
+        APIErrorBuilder::new(ValidationError)
+            .detail("The id you provided is not valid.")
+            .with_field("id", id.into())
+            .with_field("expected_type", expected.into())
+            .build()
+    })
+}
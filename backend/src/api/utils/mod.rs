@@ -1,2 +1,5 @@
 pub(crate) mod db;
+pub mod extract;
 pub mod friendly_id;
+pub mod secret;
+pub mod validation;
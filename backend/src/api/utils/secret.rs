@@ -0,0 +1,22 @@
+//! Opaque secrets handed to clients, such as refresh tokens.
+//!
+//! Only a hash of a secret is ever stored, so the database alone can't be used to impersonate anyone. The
+//! secrets are random enough that a plain SHA-256 is all the hashing they need.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+const SECRET_BYTES: usize = 32;
+
+/// Generates a new secret, encoded as hex.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    hex::encode(bytes)
+}
+
+/// Hashes a secret for storing or looking it up.
+pub fn hash_secret(secret: &str) -> Vec<u8> {
+    Sha256::digest(secret.as_bytes()).to_vec()
+}
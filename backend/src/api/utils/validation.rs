@@ -0,0 +1,90 @@
+use crate::api::error::{APIError, APIErrorBuilder, ErrorType};
+use axum::async_trait;
+use axum::body::HttpBody;
+use axum::extract::{FromRequest, Json};
+use axum::http::Request;
+use axum::BoxError;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Extracts a JSON request body and checks it against its `validator` rules.
+///
+/// Bodies that aren't JSON, don't match the shape of `T` or break one of its rules are all rejected with a
+/// validation error, so handlers can use the payload straight away.
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = APIError;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        // Parsing into a value first keeps syntax errors apart from errors in the shape of the body.
+        let Json(value) = Json::<Value>::from_request(req, state)
+            .await
+            .map_err(|rejection| {
+                APIErrorBuilder::new(ErrorType::ValidationError)
+                    .detail(&rejection.body_text())
+                    .build()
+            })?;
+
+        let payload: T = serde_path_to_error::deserialize(value).map_err(deserialize_error)?;
+        payload.validate()?;
+
+        Ok(Self(payload))
+    }
+}
+
+/// Reports a body that doesn't match the shape of its type as an error on the field it went wrong at.
+fn deserialize_error(error: serde_path_to_error::Error<serde_json::Error>) -> APIError {
+    let path = error.path().to_string();
+    let message = error.inner().to_string();
+
+    // serde reports a missing field on the struct it belongs to rather than on the field itself
+    let (field, code) = match message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.strip_suffix('`'))
+    {
+        Some(missing) if path == "." => (missing.to_string(), "required"),
+        Some(missing) => (format!("{}.{}", path, missing), "required"),
+        None => (path, "invalid"),
+    };
+
+    let builder = APIErrorBuilder::new(ErrorType::ValidationError);
+    if field == "." {
+        return builder.detail(&message).build();
+    }
+
+    let mut fields = Map::new();
+    fields.insert(
+        field,
+        json!([{ "code": code, "message": message, "params": {} }]),
+    );
+
+    builder
+        .detail("One or more fields are invalid.")
+        .with_field("errors", Value::Object(fields))
+        .build()
+}
+
+/// Builds a validation error for a single field, in the same shape as errors reported by `validator`.
+pub fn field_error(field: &'static str, code: &'static str, message: &'static str) -> APIError {
+    let mut errors = ValidationErrors::new();
+    errors.add(field, new_validation_error(code, message));
+    errors.into()
+}
+
+/// Creates a `validator` error with a human-readable message.
+pub fn new_validation_error(code: &'static str, message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(Cow::Borrowed(message));
+    error
+}
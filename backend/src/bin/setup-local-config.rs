@@ -1,5 +1,5 @@
 use pasetors::keys::{AsymmetricKeyPair, Generate};
-use pasetors::paserk::FormatAsPaserk;
+use pasetors::paserk::{FormatAsPaserk, Id};
 use pasetors::version4::V4;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -7,9 +7,23 @@ use std::fs;
 const LOCAL_CONFIG_FILE: &str = "config/local.toml";
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
-struct Auth {
-    private_key: String,
+struct AuthKey {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private_key: Option<String>,
     public_key: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    retired: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+struct Auth {
+    #[serde(default)]
+    keys: Vec<AuthKey>,
+    // the single key pair from before keys could be rotated, moved into `keys` when found
+    #[serde(skip_serializing)]
+    private_key: Option<String>,
+    #[serde(skip_serializing)]
+    public_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -28,12 +42,15 @@ struct Settings {
     app: Option<App>,
     database: Option<Database>,
     auth: Option<Auth>,
+    // any other settings, kept as they are
+    #[serde(flatten)]
+    other: toml::Table,
 }
 
 fn main() {
     let mut settings = get_settings();
 
-    add_auth_keys(&mut settings);
+    add_auth_key(&mut settings);
 
     write_settings(&settings);
 }
@@ -56,7 +73,22 @@ fn write_settings(settings: &Settings) {
     fs::write(LOCAL_CONFIG_FILE, contents).unwrap();
 }
 
-fn add_auth_keys(settings: &mut Settings) {
+/// Adds a new key to sign tokens with. Existing keys are kept so that the tokens they signed stay valid.
+fn add_auth_key(settings: &mut Settings) {
+    let auth = settings.auth.get_or_insert_with(Auth::default);
+
+    if let (Some(private_key), Some(public_key)) = (auth.private_key.take(), auth.public_key.take())
+    {
+        auth.keys.insert(
+            0,
+            AuthKey {
+                private_key: Some(private_key),
+                public_key,
+                retired: false,
+            },
+        );
+    }
+
     let sk = AsymmetricKeyPair::<V4>::generate().unwrap();
 
     let mut public = String::new();
@@ -65,8 +97,17 @@ fn add_auth_keys(settings: &mut Settings) {
     let mut private = String::new();
     sk.secret.fmt(&mut private).unwrap();
 
-    settings.auth = Some(Auth {
-        private_key: private,
+    let mut id = String::new();
+    Id::from(&sk.public).fmt(&mut id).unwrap();
+    println!("added signing key {}", id);
+
+    auth.keys.push(AuthKey {
+        private_key: Some(private),
         public_key: public,
+        retired: false,
     });
 }
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
@@ -0,0 +1,194 @@
+use crate::db::schema::{bill_items, bill_splits, bills, sql_types};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::dsl::{AsSelect, Select};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::bills)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Bill {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub payer_id: Uuid,
+    pub description: String,
+    pub amount: i64,
+    pub currency: String,
+    pub due_date: Option<chrono::NaiveDate>,
+    pub split_method: SplitMethod,
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+    pub template_id: Option<Uuid>,
+    pub status: BillStatus,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::bills)]
+pub struct NewBill {
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub payer_id: Uuid,
+    pub description: String,
+    pub amount: i64,
+    pub currency: String,
+    pub due_date: Option<chrono::NaiveDate>,
+    pub split_method: SplitMethod,
+    pub template_id: Option<Uuid>,
+    pub status: BillStatus,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::bills)]
+pub struct BillChangeset {
+    pub payer_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub amount: Option<i64>,
+    pub currency: Option<String>,
+    pub due_date: Option<Option<chrono::NaiveDate>>,
+    pub split_method: Option<SplitMethod>,
+    pub status: Option<BillStatus>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Insertable, Clone)]
+#[diesel(table_name = crate::db::schema::bill_splits)]
+#[diesel(primary_key(bill_id, user_id))]
+#[diesel(belongs_to(Bill))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BillSplit {
+    pub bill_id: Uuid,
+    pub user_id: Uuid,
+    pub amount: i64,
+    pub value: i64,
+}
+
+/// A line item on a bill, such as something bought on a shopping trip.
+#[derive(Queryable, Selectable, Identifiable, Associations)]
+#[diesel(table_name = crate::db::schema::bill_items)]
+#[diesel(belongs_to(Bill))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BillItem {
+    pub id: Uuid,
+    pub bill_id: Uuid,
+    pub shopping_item_id: Option<Uuid>,
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub category: Option<String>,
+    #[allow(dead_code)]
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::bill_items)]
+pub struct NewBillItem {
+    pub bill_id: Uuid,
+    pub shopping_item_id: Option<Uuid>,
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Whether a bill counts towards the balances of a household yet.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(sql_type = sql_types::BillStatus)]
+#[serde(rename_all = "snake_case")]
+pub enum BillStatus {
+    /// The amount or split still needs to be filled in.
+    Draft,
+    /// The bill has been split between members.
+    Posted,
+}
+
+impl ToSql<sql_types::BillStatus, Pg> for BillStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            BillStatus::Draft => out.write_all(b"draft")?,
+            BillStatus::Posted => out.write_all(b"posted")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::BillStatus, Pg> for BillStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"draft" => Ok(BillStatus::Draft),
+            b"posted" => Ok(BillStatus::Posted),
+            _ => Err("Unrecognized bill status".into()),
+        }
+    }
+}
+
+/// How the amount of a bill is divided between members.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(sql_type = sql_types::BillSplitMethod)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitMethod {
+    /// Everyone pays the same amount.
+    Equal,
+    /// Each member pays the amount given, in minor units.
+    Exact,
+    /// Each member pays a percentage, given in basis points (1/100th of a percent).
+    Percentage,
+    /// Each member pays in proportion to the number of shares given.
+    Shares,
+}
+
+impl ToSql<sql_types::BillSplitMethod, Pg> for SplitMethod {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            SplitMethod::Equal => out.write_all(b"equal")?,
+            SplitMethod::Exact => out.write_all(b"exact")?,
+            SplitMethod::Percentage => out.write_all(b"percentage")?,
+            SplitMethod::Shares => out.write_all(b"shares")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::BillSplitMethod, Pg> for SplitMethod {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"equal" => Ok(SplitMethod::Equal),
+            b"exact" => Ok(SplitMethod::Exact),
+            b"percentage" => Ok(SplitMethod::Percentage),
+            b"shares" => Ok(SplitMethod::Shares),
+            _ => Err("Unrecognized bill split method".into()),
+        }
+    }
+}
+
+type All = Select<bills::table, AsSelect<Bill, Pg>>;
+type AllSplits = Select<bill_splits::table, AsSelect<BillSplit, Pg>>;
+type AllItems = Select<bill_items::table, AsSelect<BillItem, Pg>>;
+
+impl Bill {
+    pub fn all() -> All {
+        bills::table.select(Bill::as_select())
+    }
+}
+
+impl BillSplit {
+    pub fn all() -> AllSplits {
+        bill_splits::table.select(BillSplit::as_select())
+    }
+}
+
+impl BillItem {
+    pub fn all() -> AllItems {
+        bill_items::table.select(BillItem::as_select())
+    }
+}
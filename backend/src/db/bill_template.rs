@@ -0,0 +1,119 @@
+use crate::db::bill::SplitMethod;
+use crate::db::schema::{bill_template_splits, bill_templates, sql_types};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::dsl::{AsSelect, Select};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use std::io::Write;
+use uuid::Uuid;
+
+/// A bill that repeats on a schedule, such as rent or power.
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::bill_templates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BillTemplate {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub payer_id: Uuid,
+    pub description: String,
+    pub amount: i64,
+    pub currency: String,
+    pub split_method: SplitMethod,
+    pub recurrence: BillRecurrenceKind,
+    pub recurrence_interval: i32,
+    pub start_date: chrono::NaiveDate,
+    pub next_due_date: chrono::NaiveDate,
+    pub paused_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::bill_templates)]
+pub struct NewBillTemplate {
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub payer_id: Uuid,
+    pub description: String,
+    pub amount: i64,
+    pub currency: String,
+    pub split_method: SplitMethod,
+    pub recurrence: BillRecurrenceKind,
+    pub recurrence_interval: i32,
+    pub start_date: chrono::NaiveDate,
+    pub next_due_date: chrono::NaiveDate,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::bill_templates)]
+pub struct BillTemplateChangeset {
+    pub payer_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub amount: Option<i64>,
+    pub currency: Option<String>,
+    pub split_method: Option<SplitMethod>,
+    pub recurrence: Option<BillRecurrenceKind>,
+    pub recurrence_interval: Option<i32>,
+    pub next_due_date: Option<chrono::NaiveDate>,
+    pub paused_at: Option<Option<chrono::NaiveDateTime>>,
+    pub failure_count: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Insertable, Clone)]
+#[diesel(table_name = crate::db::schema::bill_template_splits)]
+#[diesel(primary_key(template_id, user_id))]
+#[diesel(belongs_to(BillTemplate, foreign_key = template_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BillTemplateSplit {
+    pub template_id: Uuid,
+    pub user_id: Uuid,
+    pub amount: i64,
+    pub value: i64,
+}
+
+/// How a recurring bill repeats. The number of weeks or months is stored separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = sql_types::BillRecurrence)]
+pub enum BillRecurrenceKind {
+    Weekly,
+    Monthly,
+}
+
+impl ToSql<sql_types::BillRecurrence, Pg> for BillRecurrenceKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            BillRecurrenceKind::Weekly => out.write_all(b"weekly")?,
+            BillRecurrenceKind::Monthly => out.write_all(b"monthly")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::BillRecurrence, Pg> for BillRecurrenceKind {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"weekly" => Ok(BillRecurrenceKind::Weekly),
+            b"monthly" => Ok(BillRecurrenceKind::Monthly),
+            _ => Err("Unrecognized bill recurrence".into()),
+        }
+    }
+}
+
+type All = Select<bill_templates::table, AsSelect<BillTemplate, Pg>>;
+type AllSplits = Select<bill_template_splits::table, AsSelect<BillTemplateSplit, Pg>>;
+
+impl BillTemplate {
+    pub fn all() -> All {
+        bill_templates::table.select(BillTemplate::as_select())
+    }
+}
+
+impl BillTemplateSplit {
+    pub fn all() -> AllSplits {
+        bill_template_splits::table.select(BillTemplateSplit::as_select())
+    }
+}
@@ -0,0 +1,10 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// The secret a user puts in their calendar feed url, since calendar apps can't sign in. Only a hash of it is stored.
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::calendar_feed_tokens)]
+pub struct NewCalendarFeedToken {
+    pub user_id: Uuid,
+    pub token_hash: Vec<u8>,
+}
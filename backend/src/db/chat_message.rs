@@ -0,0 +1,32 @@
+use crate::db::schema::chat_messages;
+use diesel::dsl::{AsSelect, Select};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable, Clone)]
+#[diesel(table_name = crate::db::schema::chat_messages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ChatMessage {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::chat_messages)]
+pub struct NewChatMessage {
+    pub household_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub body: String,
+}
+
+type All = Select<chat_messages::table, AsSelect<ChatMessage, Pg>>;
+
+impl ChatMessage {
+    pub fn all() -> All {
+        chat_messages::table.select(ChatMessage::as_select())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::db::schema::{chore_occurrences, chores, sql_types};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::dsl::{AsSelect, Select};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::chores)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Chore {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub recurrence: RecurrenceKind,
+    pub recurrence_interval: Option<i32>,
+    pub recurrence_weekdays: Option<i32>,
+    pub rotate: bool,
+    pub start_date: chrono::NaiveDate,
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+    pub effort: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::chores)]
+pub struct NewChore {
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    pub recurrence: RecurrenceKind,
+    pub recurrence_interval: Option<i32>,
+    pub recurrence_weekdays: Option<i32>,
+    pub rotate: bool,
+    pub start_date: chrono::NaiveDate,
+    pub effort: i32,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::chores)]
+pub struct ChoreChangeset {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub recurrence: Option<RecurrenceKind>,
+    pub recurrence_interval: Option<Option<i32>>,
+    pub recurrence_weekdays: Option<Option<i32>>,
+    pub rotate: Option<bool>,
+    pub effort: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations)]
+#[diesel(table_name = crate::db::schema::chore_occurrences)]
+#[diesel(belongs_to(Chore))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ChoreOccurrence {
+    pub id: Uuid,
+    pub chore_id: Uuid,
+    pub due_date: chrono::NaiveDate,
+    pub assignee_id: Option<Uuid>,
+    pub status: OccurrenceStatus,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+    #[allow(dead_code)]
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::chore_occurrences)]
+pub struct NewChoreOccurrence {
+    pub chore_id: Uuid,
+    pub due_date: chrono::NaiveDate,
+    pub assignee_id: Option<Uuid>,
+}
+
+/// How a chore repeats. The parameters of each kind are stored in separate columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow)]
+#[diesel(sql_type = sql_types::ChoreRecurrence)]
+pub enum RecurrenceKind {
+    Daily,
+    Weekly,
+    EveryNDays,
+    Weekdays,
+}
+
+impl ToSql<sql_types::ChoreRecurrence, Pg> for RecurrenceKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            RecurrenceKind::Daily => out.write_all(b"daily")?,
+            RecurrenceKind::Weekly => out.write_all(b"weekly")?,
+            RecurrenceKind::EveryNDays => out.write_all(b"every_n_days")?,
+            RecurrenceKind::Weekdays => out.write_all(b"weekdays")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::ChoreRecurrence, Pg> for RecurrenceKind {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"daily" => Ok(RecurrenceKind::Daily),
+            b"weekly" => Ok(RecurrenceKind::Weekly),
+            b"every_n_days" => Ok(RecurrenceKind::EveryNDays),
+            b"weekdays" => Ok(RecurrenceKind::Weekdays),
+            _ => Err("Unrecognized chore recurrence".into()),
+        }
+    }
+}
+
+/// The state of a single occurrence of a chore.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(sql_type = sql_types::ChoreOccurrenceStatus)]
+#[serde(rename_all = "snake_case")]
+pub enum OccurrenceStatus {
+    Pending,
+    Completed,
+    Skipped,
+}
+
+impl ToSql<sql_types::ChoreOccurrenceStatus, Pg> for OccurrenceStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            OccurrenceStatus::Pending => out.write_all(b"pending")?,
+            OccurrenceStatus::Completed => out.write_all(b"completed")?,
+            OccurrenceStatus::Skipped => out.write_all(b"skipped")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::ChoreOccurrenceStatus, Pg> for OccurrenceStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"pending" => Ok(OccurrenceStatus::Pending),
+            b"completed" => Ok(OccurrenceStatus::Completed),
+            b"skipped" => Ok(OccurrenceStatus::Skipped),
+            _ => Err("Unrecognized chore occurrence status".into()),
+        }
+    }
+}
+
+type All = Select<chores::table, AsSelect<Chore, Pg>>;
+type AllOccurrences = Select<chore_occurrences::table, AsSelect<ChoreOccurrence, Pg>>;
+
+impl Chore {
+    pub fn all() -> All {
+        chores::table.select(Chore::as_select())
+    }
+}
+
+impl ChoreOccurrence {
+    pub fn all() -> AllOccurrences {
+        chore_occurrences::table.select(ChoreOccurrence::as_select())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::db::schema::documents;
+use diesel::dsl::{AsSelect, Select};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// The metadata of a file uploaded to a household. The contents are kept in the storage backend.
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::documents)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Document {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub uploaded_by: Option<Uuid>,
+    pub name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::documents)]
+pub struct NewDocument {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub uploaded_by: Option<Uuid>,
+    pub name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::documents)]
+pub struct DocumentChangeset {
+    pub name: Option<String>,
+}
+
+type All = Select<documents::table, AsSelect<Document, Pg>>;
+
+impl Document {
+    pub fn all() -> All {
+        documents::table.select(Document::as_select())
+    }
+}
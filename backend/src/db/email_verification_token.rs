@@ -0,0 +1,12 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// A link sent to an email address to prove that it belongs to the user.
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::email_verification_tokens)]
+pub struct NewEmailVerificationToken {
+    pub user_id: Uuid,
+    pub email: String,
+    pub token_hash: Vec<u8>,
+    pub expires_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,162 @@
+use crate::db::schema::{event_rsvps, events, sql_types};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::dsl::{AsSelect, Select};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Event {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub starts_at: chrono::NaiveDateTime,
+    pub ends_at: chrono::NaiveDateTime,
+    pub timezone: String,
+    pub all_day: bool,
+    pub recurrence_frequency: Option<EventFrequency>,
+    pub recurrence_interval: i32,
+    pub recurrence_until: Option<chrono::NaiveDate>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::events)]
+pub struct NewEvent {
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub starts_at: chrono::NaiveDateTime,
+    pub ends_at: chrono::NaiveDateTime,
+    pub timezone: String,
+    pub all_day: bool,
+    pub recurrence_frequency: Option<EventFrequency>,
+    pub recurrence_interval: i32,
+    pub recurrence_until: Option<chrono::NaiveDate>,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::events)]
+pub struct EventChangeset {
+    pub title: Option<String>,
+    pub description: Option<Option<String>>,
+    pub location: Option<Option<String>>,
+    pub starts_at: Option<chrono::NaiveDateTime>,
+    pub ends_at: Option<chrono::NaiveDateTime>,
+    pub timezone: Option<String>,
+    pub all_day: Option<bool>,
+    pub recurrence_frequency: Option<Option<EventFrequency>>,
+    pub recurrence_interval: Option<i32>,
+    pub recurrence_until: Option<Option<chrono::NaiveDate>>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations, Insertable)]
+#[diesel(table_name = crate::db::schema::event_rsvps)]
+#[diesel(primary_key(event_id, user_id))]
+#[diesel(belongs_to(Event))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EventRsvp {
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub status: RsvpStatus,
+    pub responded_at: chrono::NaiveDateTime,
+}
+
+/// How often a recurring event repeats.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(sql_type = sql_types::EventFrequency)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl ToSql<sql_types::EventFrequency, Pg> for EventFrequency {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            EventFrequency::Daily => out.write_all(b"daily")?,
+            EventFrequency::Weekly => out.write_all(b"weekly")?,
+            EventFrequency::Monthly => out.write_all(b"monthly")?,
+            EventFrequency::Yearly => out.write_all(b"yearly")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::EventFrequency, Pg> for EventFrequency {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"daily" => Ok(EventFrequency::Daily),
+            b"weekly" => Ok(EventFrequency::Weekly),
+            b"monthly" => Ok(EventFrequency::Monthly),
+            b"yearly" => Ok(EventFrequency::Yearly),
+            _ => Err("Unrecognized event frequency".into()),
+        }
+    }
+}
+
+/// A member's reply to an event.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(sql_type = sql_types::RsvpStatus)]
+#[serde(rename_all = "snake_case")]
+pub enum RsvpStatus {
+    Going,
+    Maybe,
+    Declined,
+}
+
+impl ToSql<sql_types::RsvpStatus, Pg> for RsvpStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            RsvpStatus::Going => out.write_all(b"going")?,
+            RsvpStatus::Maybe => out.write_all(b"maybe")?,
+            RsvpStatus::Declined => out.write_all(b"declined")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::RsvpStatus, Pg> for RsvpStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"going" => Ok(RsvpStatus::Going),
+            b"maybe" => Ok(RsvpStatus::Maybe),
+            b"declined" => Ok(RsvpStatus::Declined),
+            _ => Err("Unrecognized RSVP status".into()),
+        }
+    }
+}
+
+type All = Select<events::table, AsSelect<Event, Pg>>;
+type AllRsvps = Select<event_rsvps::table, AsSelect<EventRsvp, Pg>>;
+
+impl Event {
+    pub fn all() -> All {
+        events::table.select(Event::as_select())
+    }
+}
+
+impl EventRsvp {
+    pub fn all() -> AllRsvps {
+        event_rsvps::table.select(EventRsvp::as_select())
+    }
+}
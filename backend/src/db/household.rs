@@ -0,0 +1,117 @@
+use crate::db::schema::{household_members, households, sql_types};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::dsl::{AsSelect, Eq, Filter, InnerJoin, Select};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::households)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Household {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::households)]
+pub struct NewHousehold {
+    pub name: String,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::db::schema::households)]
+pub struct HouseholdChangeset {
+    pub name: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations)]
+#[diesel(table_name = crate::db::schema::household_members)]
+#[diesel(primary_key(household_id, user_id))]
+#[diesel(belongs_to(Household))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct HouseholdMember {
+    pub household_id: Uuid,
+    pub user_id: Uuid,
+    pub role: HouseholdRole,
+    pub joined_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::household_members)]
+pub struct NewHouseholdMember {
+    pub household_id: Uuid,
+    pub user_id: Uuid,
+    pub role: HouseholdRole,
+}
+
+/// The role a user holds within a household.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, AsExpression, FromSqlRow, Serialize, Deserialize, ToSchema,
+)]
+#[diesel(sql_type = sql_types::HouseholdRole)]
+#[serde(rename_all = "snake_case")]
+pub enum HouseholdRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl HouseholdRole {
+    /// Whether this role is allowed to change the household and its settings.
+    pub fn can_manage(&self) -> bool {
+        matches!(self, HouseholdRole::Owner | HouseholdRole::Admin)
+    }
+}
+
+impl ToSql<sql_types::HouseholdRole, Pg> for HouseholdRole {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            HouseholdRole::Owner => out.write_all(b"owner")?,
+            HouseholdRole::Admin => out.write_all(b"admin")?,
+            HouseholdRole::Member => out.write_all(b"member")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::HouseholdRole, Pg> for HouseholdRole {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"owner" => Ok(HouseholdRole::Owner),
+            b"admin" => Ok(HouseholdRole::Admin),
+            b"member" => Ok(HouseholdRole::Member),
+            _ => Err("Unrecognized household role".into()),
+        }
+    }
+}
+
+type All = Select<households::table, AsSelect<Household, Pg>>;
+type ForUser = Select<
+    Filter<
+        InnerJoin<households::table, household_members::table>,
+        Eq<household_members::user_id, Uuid>,
+    >,
+    (AsSelect<Household, Pg>, AsSelect<HouseholdMember, Pg>),
+>;
+
+impl Household {
+    pub fn all() -> All {
+        households::table.select(Household::as_select())
+    }
+
+    /// All households the given user is a member of, along with their membership.
+    pub fn for_user(user_id: Uuid) -> ForUser {
+        households::table
+            .inner_join(household_members::table)
+            .filter(household_members::user_id.eq(user_id))
+            .select((Household::as_select(), HouseholdMember::as_select()))
+    }
+}
@@ -0,0 +1,67 @@
+use crate::db::schema::household_invites;
+use diesel::dsl::{AsSelect, Eq, Filter, Or, Select};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::household_invites)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct HouseholdInvite {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Uuid,
+    pub code: String,
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub expires_at: chrono::NaiveDateTime,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::household_invites)]
+pub struct NewHouseholdInvite {
+    pub household_id: Uuid,
+    pub created_by: Uuid,
+    pub code: String,
+    pub token_hash: Vec<u8>,
+    pub max_uses: Option<i32>,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+type All = Select<household_invites::table, AsSelect<HouseholdInvite, Pg>>;
+type ByCodeOrToken<'a> = Filter<
+    All,
+    Or<Eq<household_invites::code, &'a str>, Eq<household_invites::token_hash, Vec<u8>>>,
+>;
+
+impl HouseholdInvite {
+    pub fn all() -> All {
+        household_invites::table.select(HouseholdInvite::as_select())
+    }
+
+    /// Finds an invite by either its short code or the hash of its link token.
+    pub fn by_code_or_token_hash(code: &str, token_hash: Vec<u8>) -> ByCodeOrToken<'_> {
+        Self::all().filter(
+            household_invites::code
+                .eq(code)
+                .or(household_invites::token_hash.eq(token_hash)),
+        )
+    }
+
+    pub(crate) fn is_expired(&self) -> bool {
+        self.expires_at < chrono::Utc::now().naive_utc()
+    }
+
+    pub(crate) fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub(crate) fn is_used_up(&self) -> bool {
+        self.max_uses
+            .is_some_and(|max_uses| self.use_count >= max_uses)
+    }
+}
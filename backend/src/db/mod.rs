@@ -1,4 +1,19 @@
+pub mod bill;
+pub mod bill_template;
+pub mod calendar_feed_token;
+pub mod chat_message;
+pub mod chore;
 pub mod database;
+pub mod document;
+pub mod email_verification_token;
+pub mod event;
+pub mod household;
+pub mod invite;
+pub mod password_reset_token;
 pub mod refresh_token;
+pub mod revoked_access_token;
+pub mod revoked_session;
 pub mod schema;
+pub mod settlement;
+pub mod shopping;
 pub mod user;
@@ -0,0 +1,11 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// A single use link to choose a new password, sent to a user who has forgotten theirs.
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::password_reset_tokens)]
+pub struct NewPasswordResetToken {
+    pub user_id: Uuid,
+    pub token_hash: Vec<u8>,
+    pub expires_at: chrono::NaiveDateTime,
+}
@@ -1,7 +1,14 @@
+use crate::db::schema::refresh_tokens;
+use diesel::dsl::{AsSelect, Select};
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use uuid::Uuid;
 
-#[derive(Queryable, Selectable)]
+/// A session on one of a user's devices, which can be kept alive with its refresh token.
+///
+/// Every refresh token the session has ever had belongs to the same family, so the session is revoked if a
+/// token that was already used is presented again.
+#[derive(Queryable, Selectable, Identifiable)]
 #[diesel(table_name = crate::db::schema::refresh_tokens)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct RefreshToken {
@@ -9,10 +16,23 @@ pub struct RefreshToken {
     pub user_id: Uuid,
     pub expires_at: chrono::NaiveDateTime,
     pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
     pub updated_at: Option<chrono::NaiveDateTime>,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: chrono::NaiveDateTime,
+    /// A hash of the secret the client sends to refresh its session. Changes every time it is used.
+    pub token_hash: Vec<u8>,
 }
 
+type All = Select<refresh_tokens::table, AsSelect<RefreshToken, Pg>>;
+
 impl RefreshToken {
+    pub fn all() -> All {
+        refresh_tokens::table.select(RefreshToken::as_select())
+    }
+
     pub(crate) fn is_expired(&self) -> bool {
         self.expires_at < chrono::Utc::now().naive_utc()
     }
@@ -22,4 +42,16 @@ impl RefreshToken {
 #[diesel(table_name = crate::db::schema::refresh_tokens)]
 pub struct NewRefreshToken {
     pub user_id: Uuid,
+    pub device_name: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub token_hash: Vec<u8>,
+}
+
+/// A refresh token that has been rotated out of its session.
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::used_refresh_tokens)]
+pub struct NewUsedRefreshToken {
+    pub token_hash: Vec<u8>,
+    pub session_id: Uuid,
 }
@@ -0,0 +1,10 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::revoked_access_tokens)]
+pub struct NewRevokedAccessToken {
+    pub jti: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: chrono::NaiveDateTime,
+}
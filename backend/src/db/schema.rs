@@ -1,5 +1,281 @@
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "bill_recurrence"))]
+    pub struct BillRecurrence;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "bill_split_method"))]
+    pub struct BillSplitMethod;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "bill_status"))]
+    pub struct BillStatus;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "chore_occurrence_status"))]
+    pub struct ChoreOccurrenceStatus;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "chore_recurrence"))]
+    pub struct ChoreRecurrence;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "event_frequency"))]
+    pub struct EventFrequency;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "household_role"))]
+    pub struct HouseholdRole;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "rsvp_status"))]
+    pub struct RsvpStatus;
+}
+
+diesel::table! {
+    bill_items (id) {
+        id -> Uuid,
+        bill_id -> Uuid,
+        shopping_item_id -> Nullable<Uuid>,
+        name -> Text,
+        quantity -> Nullable<Float8>,
+        unit -> Nullable<Text>,
+        category -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    bill_splits (bill_id, user_id) {
+        bill_id -> Uuid,
+        user_id -> Uuid,
+        amount -> Int8,
+        value -> Int8,
+    }
+}
+
+diesel::table! {
+    bill_template_splits (template_id, user_id) {
+        template_id -> Uuid,
+        user_id -> Uuid,
+        amount -> Int8,
+        value -> Int8,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BillSplitMethod;
+    use super::sql_types::BillRecurrence;
+
+    bill_templates (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        payer_id -> Uuid,
+        description -> Text,
+        amount -> Int8,
+        currency -> Text,
+        split_method -> BillSplitMethod,
+        recurrence -> BillRecurrence,
+        recurrence_interval -> Int4,
+        start_date -> Date,
+        next_due_date -> Date,
+        paused_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+        failure_count -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BillSplitMethod;
+    use super::sql_types::BillStatus;
+
+    bills (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        payer_id -> Uuid,
+        description -> Text,
+        amount -> Int8,
+        currency -> Text,
+        due_date -> Nullable<Date>,
+        split_method -> BillSplitMethod,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+        template_id -> Nullable<Uuid>,
+        status -> BillStatus,
+    }
+}
+
+diesel::table! {
+    calendar_feed_tokens (user_id) {
+        user_id -> Uuid,
+        created_at -> Timestamp,
+        token_hash -> Bytea,
+    }
+}
+
+diesel::table! {
+    chat_messages (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        user_id -> Nullable<Uuid>,
+        body -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ChoreOccurrenceStatus;
+
+    chore_occurrences (id) {
+        id -> Uuid,
+        chore_id -> Uuid,
+        due_date -> Date,
+        assignee_id -> Nullable<Uuid>,
+        status -> ChoreOccurrenceStatus,
+        resolved_by -> Nullable<Uuid>,
+        resolved_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ChoreRecurrence;
+
+    chores (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        name -> Text,
+        description -> Nullable<Text>,
+        recurrence -> ChoreRecurrence,
+        recurrence_interval -> Nullable<Int4>,
+        recurrence_weekdays -> Nullable<Int4>,
+        rotate -> Bool,
+        start_date -> Date,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+        effort -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RsvpStatus;
+
+    event_rsvps (event_id, user_id) {
+        event_id -> Uuid,
+        user_id -> Uuid,
+        status -> RsvpStatus,
+        responded_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    documents (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        uploaded_by -> Nullable<Uuid>,
+        name -> Text,
+        content_type -> Text,
+        size_bytes -> Int8,
+        storage_key -> Text,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    email_verification_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        email -> Text,
+        token_hash -> Bytea,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::EventFrequency;
+
+    events (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        title -> Text,
+        description -> Nullable<Text>,
+        location -> Nullable<Text>,
+        starts_at -> Timestamp,
+        ends_at -> Timestamp,
+        timezone -> Text,
+        all_day -> Bool,
+        recurrence_frequency -> Nullable<EventFrequency>,
+        recurrence_interval -> Int4,
+        recurrence_until -> Nullable<Date>,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    household_invites (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Uuid,
+        code -> Text,
+        max_uses -> Nullable<Int4>,
+        use_count -> Int4,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+        token_hash -> Bytea,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::HouseholdRole;
+
+    household_members (household_id, user_id) {
+        household_id -> Uuid,
+        user_id -> Uuid,
+        role -> HouseholdRole,
+        joined_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    households (id) {
+        id -> Uuid,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    password_reset_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token_hash -> Bytea,
+        expires_at -> Timestamp,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     refresh_tokens (id) {
         id -> Uuid,
@@ -7,6 +283,78 @@ diesel::table! {
         expires_at -> Timestamp,
         created_at -> Timestamp,
         updated_at -> Nullable<Timestamp>,
+        device_name -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+        last_used_at -> Timestamp,
+        token_hash -> Bytea,
+    }
+}
+
+diesel::table! {
+    revoked_access_tokens (jti) {
+        jti -> Uuid,
+        user_id -> Uuid,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    revoked_sessions (session_id) {
+        session_id -> Uuid,
+        user_id -> Uuid,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    settlements (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        from_user_id -> Uuid,
+        to_user_id -> Uuid,
+        amount -> Int8,
+        currency -> Text,
+        note -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    shopping_items (id) {
+        id -> Uuid,
+        list_id -> Uuid,
+        name -> Text,
+        quantity -> Nullable<Float8>,
+        unit -> Nullable<Text>,
+        category -> Nullable<Text>,
+        added_by -> Nullable<Uuid>,
+        checked_by -> Nullable<Uuid>,
+        checked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    shopping_lists (id) {
+        id -> Uuid,
+        household_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    used_refresh_tokens (token_hash) {
+        session_id -> Uuid,
+        used_at -> Timestamp,
+        token_hash -> Bytea,
     }
 }
 
@@ -19,12 +367,71 @@ diesel::table! {
         password -> Text,
         created_at -> Timestamp,
         updated_at -> Nullable<Timestamp>,
+        tokens_valid_after -> Nullable<Timestamp>,
+        email_verified_at -> Nullable<Timestamp>,
     }
 }
 
+diesel::joinable!(bill_items -> bills (bill_id));
+diesel::joinable!(bill_items -> shopping_items (shopping_item_id));
+diesel::joinable!(bill_splits -> bills (bill_id));
+diesel::joinable!(bill_splits -> users (user_id));
+diesel::joinable!(bill_template_splits -> bill_templates (template_id));
+diesel::joinable!(bill_template_splits -> users (user_id));
+diesel::joinable!(bill_templates -> households (household_id));
+diesel::joinable!(bills -> bill_templates (template_id));
+diesel::joinable!(bills -> households (household_id));
+diesel::joinable!(calendar_feed_tokens -> users (user_id));
+diesel::joinable!(chat_messages -> households (household_id));
+diesel::joinable!(chat_messages -> users (user_id));
+diesel::joinable!(chore_occurrences -> chores (chore_id));
+diesel::joinable!(chores -> households (household_id));
+diesel::joinable!(chores -> users (created_by));
+diesel::joinable!(documents -> households (household_id));
+diesel::joinable!(documents -> users (uploaded_by));
+diesel::joinable!(email_verification_tokens -> users (user_id));
+diesel::joinable!(event_rsvps -> events (event_id));
+diesel::joinable!(event_rsvps -> users (user_id));
+diesel::joinable!(events -> households (household_id));
+diesel::joinable!(events -> users (created_by));
+diesel::joinable!(household_invites -> households (household_id));
+diesel::joinable!(household_invites -> users (created_by));
+diesel::joinable!(household_members -> households (household_id));
+diesel::joinable!(household_members -> users (user_id));
+diesel::joinable!(password_reset_tokens -> users (user_id));
 diesel::joinable!(refresh_tokens -> users (user_id));
+diesel::joinable!(revoked_access_tokens -> users (user_id));
+diesel::joinable!(revoked_sessions -> users (user_id));
+diesel::joinable!(settlements -> households (household_id));
+diesel::joinable!(shopping_items -> shopping_lists (list_id));
+diesel::joinable!(shopping_lists -> households (household_id));
+diesel::joinable!(shopping_lists -> users (created_by));
+diesel::joinable!(used_refresh_tokens -> refresh_tokens (session_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    bill_items,
+    bill_splits,
+    bill_template_splits,
+    bill_templates,
+    bills,
+    calendar_feed_tokens,
+    chat_messages,
+    chore_occurrences,
+    chores,
+    documents,
+    email_verification_tokens,
+    event_rsvps,
+    events,
+    household_invites,
+    household_members,
+    households,
+    password_reset_tokens,
     refresh_tokens,
+    revoked_access_tokens,
+    revoked_sessions,
+    settlements,
+    shopping_items,
+    shopping_lists,
+    used_refresh_tokens,
     users,
 );
@@ -0,0 +1,41 @@
+use crate::db::schema::settlements;
+use diesel::dsl::{AsSelect, Select};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+/// A payment from one member to another that pays back what they owe.
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::settlements)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Settlement {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: i64,
+    pub currency: String,
+    pub note: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::settlements)]
+pub struct NewSettlement {
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: i64,
+    pub currency: String,
+    pub note: Option<String>,
+}
+
+type All = Select<settlements::table, AsSelect<Settlement, Pg>>;
+
+impl Settlement {
+    pub fn all() -> All {
+        settlements::table.select(Settlement::as_select())
+    }
+}
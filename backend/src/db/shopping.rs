@@ -0,0 +1,88 @@
+use crate::db::schema::{shopping_items, shopping_lists};
+use diesel::dsl::{AsSelect, Select};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[derive(Queryable, Selectable, Identifiable)]
+#[diesel(table_name = crate::db::schema::shopping_lists)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShoppingList {
+    pub id: Uuid,
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::shopping_lists)]
+pub struct NewShoppingList {
+    pub household_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::shopping_lists)]
+pub struct ShoppingListChangeset {
+    pub name: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Identifiable, Associations)]
+#[diesel(table_name = crate::db::schema::shopping_items)]
+#[diesel(belongs_to(ShoppingList, foreign_key = list_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ShoppingItem {
+    pub id: Uuid,
+    pub list_id: Uuid,
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub category: Option<String>,
+    pub added_by: Option<Uuid>,
+    pub checked_by: Option<Uuid>,
+    pub checked_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
+    pub updated_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::db::schema::shopping_items)]
+pub struct NewShoppingItem {
+    pub list_id: Uuid,
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub category: Option<String>,
+    pub added_by: Option<Uuid>,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = crate::db::schema::shopping_items)]
+pub struct ShoppingItemChangeset {
+    pub name: Option<String>,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub category: Option<String>,
+    pub checked_by: Option<Option<Uuid>>,
+    pub checked_at: Option<Option<chrono::NaiveDateTime>>,
+}
+
+type All = Select<shopping_lists::table, AsSelect<ShoppingList, Pg>>;
+type AllItems = Select<shopping_items::table, AsSelect<ShoppingItem, Pg>>;
+
+impl ShoppingList {
+    pub fn all() -> All {
+        shopping_lists::table.select(ShoppingList::as_select())
+    }
+}
+
+impl ShoppingItem {
+    pub fn all() -> AllItems {
+        shopping_items::table.select(ShoppingItem::as_select())
+    }
+}
@@ -13,8 +13,11 @@ pub struct User {
     pub password: String,
     pub first_name: String,
     pub last_name: String,
+    #[allow(dead_code)]
     pub created_at: chrono::NaiveDateTime,
+    #[allow(dead_code)]
     pub updated_at: Option<chrono::NaiveDateTime>,
+    pub email_verified_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Insertable)]
@@ -34,7 +37,7 @@ impl User {
         users::table.select(User::as_select())
     }
 
-    pub fn by_email(email: &str) -> WithEmail {
+    pub fn by_email(email: &str) -> WithEmail<'_> {
         users::email.eq(email)
     }
 }
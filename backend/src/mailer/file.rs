@@ -0,0 +1,41 @@
+use super::{build_message, Email, Mailer, MailerError};
+use crate::config::Mail;
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::{AsyncFileTransport, AsyncTransport, Tokio1Executor};
+use std::path::PathBuf;
+use tracing::info;
+
+/// Writes emails to `.eml` files in a directory instead of sending them, for local development and tests.
+pub struct FileMailer {
+    from: Mailbox,
+    path: PathBuf,
+    transport: AsyncFileTransport<Tokio1Executor>,
+}
+
+impl FileMailer {
+    pub fn new(settings: &Mail) -> Result<Self, MailerError> {
+        Ok(Self {
+            from: settings.from.parse()?,
+            path: settings.path.clone().into(),
+            transport: AsyncFileTransport::new(&settings.path),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        let to = email.to.clone();
+        let subject = email.subject.clone();
+        let message = build_message(&self.from, email)?;
+
+        tokio::fs::create_dir_all(&self.path).await?;
+        let id = self.transport.send(message).await?;
+
+        let file = self.path.join(format!("{}.eml", id));
+        info!(to = to, subject = subject, file = %file.display(), "wrote email to file");
+
+        Ok(())
+    }
+}
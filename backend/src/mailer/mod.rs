@@ -0,0 +1,72 @@
+use crate::config::{MailTransport, Settings};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::Message;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::error;
+
+pub mod file;
+pub mod smtp;
+
+/// A plain text email to a single recipient.
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Error, Debug)]
+pub enum MailerError {
+    #[error("The email address is not valid.")]
+    InvalidAddress(#[from] lettre::address::AddressError),
+
+    #[error(transparent)]
+    Message(#[from] lettre::error::Error),
+
+    #[error(transparent)]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error(transparent)]
+    File(#[from] lettre::transport::file::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Something that can deliver emails to users.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), MailerError>;
+}
+
+/// Creates the mailer described by the settings.
+pub fn get_mailer(settings: &Settings) -> Arc<dyn Mailer> {
+    let mail = &settings.mail;
+    match mail.transport {
+        MailTransport::Smtp => Arc::new(smtp::SmtpMailer::new(mail).unwrap()),
+        MailTransport::File => Arc::new(file::FileMailer::new(mail).unwrap()),
+    }
+}
+
+/// Sends an email without waiting for it to be delivered, logging it if it fails.
+///
+/// Not waiting also means the time taken to respond doesn't give away whether an email was sent.
+pub fn send_in_background(mailer: Arc<dyn Mailer>, email: Email) {
+    tokio::spawn(async move {
+        let subject = email.subject.clone();
+        if let Err(e) = mailer.send(email).await {
+            error!(error = %e, subject = subject, "failed to send email");
+        }
+    });
+}
+
+fn build_message(from: &Mailbox, email: Email) -> Result<Message, MailerError> {
+    let message = Message::builder()
+        .from(from.clone())
+        .to(email.to.parse()?)
+        .subject(email.subject)
+        .body(email.body)?;
+
+    Ok(message)
+}
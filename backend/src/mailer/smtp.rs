@@ -0,0 +1,33 @@
+use super::{build_message, Email, Mailer, MailerError};
+use crate::config::Mail;
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+/// Sends emails through an SMTP server.
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(settings: &Mail) -> Result<Self, MailerError> {
+        let url = settings.smtp_url.as_deref().unwrap_or("smtp://localhost");
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(url)?.build();
+
+        Ok(Self {
+            from: settings.from.parse()?,
+            transport,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        let message = build_message(&self.from, email)?;
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
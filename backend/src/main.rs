@@ -1,32 +1,54 @@
 mod api;
 mod config;
 mod db;
+mod mailer;
+mod storage;
 
 use crate::config::Settings;
 use api::api_docs;
-use axum::http::StatusCode;
-use axum::Router;
+use api::auth::keys::KeySet;
+use api::auth::revocation::TokenRevocations;
+use api::chat::hub::ChatHub;
+use axum::body::Body;
+use axum::http::Request;
+use axum::{middleware, Router};
 use db::database;
+use mailer::Mailer;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use storage::Storage;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tower_http::LatencyUnit;
-use tracing::Level;
+use tracing::{Level, Span};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
 pub struct AppStateInternal {
     pub database_pool: database::ConnectionPool,
     pub settings: Settings,
+    pub keys: KeySet,
+    pub chat: ChatHub,
+    pub storage: Arc<dyn Storage>,
+    pub mailer: Arc<dyn Mailer>,
+    pub revocations: TokenRevocations,
 }
 
 impl AppStateInternal {
     fn new(settings: Settings) -> Self {
         let database_pool = database::get_connection_pool(&settings);
+        let storage = storage::get_storage(&settings);
+        let mailer = mailer::get_mailer(&settings);
+        let keys = KeySet::new(&settings.auth).unwrap();
 
         Self {
             database_pool,
             settings,
+            keys,
+            chat: ChatHub::default(),
+            storage,
+            mailer,
+            revocations: TokenRevocations::default(),
         }
     }
 }
@@ -52,15 +74,33 @@ async fn main() {
 
     let state = Arc::new(AppStateInternal::new(config.clone()));
 
+    // generate recurring bills in the background
+    tokio::spawn(api::bill_templates::scheduler::run(state.clone()));
+
     let app = Router::new()
         .merge(api_docs::get_swagger_ui())
         .nest("/v1", api::get_router(state.clone()))
-        .fallback(fallback)
+        .merge(api::get_well_known_router())
+        .fallback(api::not_found)
+        .layer(middleware::map_response(
+            api::middleware::method_not_allowed,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::middleware::expose_causes,
+        ))
+        .layer(middleware::from_fn(api::middleware::request_id))
         .layer(
             TraceLayer::new_for_http()
+                .make_span_with(request_span)
                 .on_request(DefaultOnRequest::new().level(Level::INFO))
                 .on_response(DefaultOnResponse::new().latency_unit(LatencyUnit::Millis)),
         )
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .layer(middleware::from_fn(
+            api::middleware::discard_untrusted_request_id,
+        ))
         .with_state(state);
 
     // run our app with hyper
@@ -68,11 +108,26 @@ async fn main() {
     tracing::info!("listening on http://{}", addr);
     tracing::debug!("docs at http://{}/swagger-ui", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
 
-async fn fallback() -> StatusCode {
-    StatusCode::UNAUTHORIZED
+/// Creates the span a request is traced in, tagged with the id of the request.
+///
+/// Only the path is logged, since some query strings carry tokens (calendar feeds and chat sockets).
+fn request_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|id| id.to_str().ok())
+        .unwrap_or_default();
+
+    tracing::debug_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        version = ?request.version(),
+        request_id = %request_id,
+    )
 }
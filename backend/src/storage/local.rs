@@ -0,0 +1,86 @@
+use super::{ByteStream, Storage, StorageError};
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+/// Keeps objects as files under a directory on the local filesystem.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Resolves a key to a path, refusing keys that could escape the root directory.
+    fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let key = Path::new(key);
+        if key.as_os_str().is_empty()
+            || !key.components().all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(StorageError::InvalidKey);
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, mut contents: ByteStream<'_>) -> Result<u64, StorageError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // write to a temporary file first, so a failed upload never leaves a partial object behind
+        let temp_path = path.with_extension(format!("{}.part", Uuid::new_v4()));
+        let result = async {
+            let mut file = fs::File::create(&temp_path).await?;
+            let mut size = 0;
+            while let Some(chunk) = contents.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk).await?;
+                size += chunk.len() as u64;
+            }
+            file.sync_all().await?;
+            fs::rename(&temp_path, &path).await?;
+
+            Ok::<_, io::Error>(size)
+        }
+        .await;
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path).await;
+        }
+
+        Ok(result?)
+    }
+
+    async fn get(&self, key: &str) -> Result<ByteStream<'static>, StorageError> {
+        let file = fs::File::open(self.path_for(key)?)
+            .await
+            .map_err(not_found)?;
+
+        Ok(Box::pin(ReaderStream::new(file)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        fs::remove_file(self.path_for(key)?)
+            .await
+            .map_err(not_found)
+    }
+}
+
+fn not_found(error: io::Error) -> StorageError {
+    match error.kind() {
+        io::ErrorKind::NotFound => StorageError::NotFound,
+        _ => StorageError::Io(error),
+    }
+}
@@ -0,0 +1,45 @@
+use crate::config::Settings;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use thiserror::Error;
+
+pub mod local;
+
+/// A stream of file contents being read from or written to storage.
+pub type ByteStream<'a> = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + 'a>>;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("The object could not be found.")]
+    NotFound,
+
+    #[error("The object key is not valid.")]
+    InvalidKey,
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Somewhere to keep the contents of uploaded files, addressed by keys such as `household/document`.
+///
+/// Implementations stream contents rather than holding them in memory.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Writes a new object, returning its size in bytes.
+    ///
+    /// Nothing is kept if the stream fails, so the caller can abort an upload by returning an error from it.
+    async fn put(&self, key: &str, contents: ByteStream<'_>) -> Result<u64, StorageError>;
+
+    async fn get(&self, key: &str) -> Result<ByteStream<'static>, StorageError>;
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Creates the storage backend described by the settings.
+pub fn get_storage(settings: &Settings) -> Arc<dyn Storage> {
+    Arc::new(local::LocalStorage::new(&settings.storage.path))
+}